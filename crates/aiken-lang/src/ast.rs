@@ -253,6 +253,104 @@ impl TypedFunction {
 }
 
 impl TypedTest {
+    /// Tags annotating the test, as declared in its doc comments. A tag is a word prefixed with
+    /// '@', on a doc line holding nothing but tags. For example:
+    ///
+    ///   /// @slow @mainnet
+    ///   test foo() { ... }
+    ///
+    /// Note that doc comments are only attached to definitions on demand.
+    pub fn tags(&self) -> Vec<String> {
+        self.doc
+            .iter()
+            .flat_map(|doc| doc.lines())
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .filter(|words| {
+                !words.is_empty()
+                    && words
+                        .iter()
+                        .all(|word| word.len() > 1 && word.starts_with('@'))
+            })
+            .flatten()
+            .map(|word| word[1..].to_string())
+            .collect()
+    }
+
+    /// The reason a test expected to fail ('fail' keyword) is expected to fail for, as declared
+    /// in its doc comments: a substring of its traces or of the error it raises. For example:
+    ///
+    ///   /// @fails_with division by zero
+    ///   test foo() fail { ... }
+    ///
+    pub fn expected_error(&self) -> Option<String> {
+        self.doc
+            .iter()
+            .flat_map(|doc| doc.lines())
+            .find_map(|line| line.trim().strip_prefix("@fails_with "))
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty())
+    }
+
+    /// The term a test is expected to evaluate to, as declared in its doc comments; compared
+    /// against what it actually evaluates to, on top of the test having to pass. For example:
+    ///
+    ///   /// @golden (con bool True)
+    ///   test foo() { ... }
+    ///
+    pub fn golden(&self) -> Option<String> {
+        self.doc
+            .iter()
+            .flat_map(|doc| doc.lines())
+            .find_map(|line| line.trim().strip_prefix("@golden "))
+            .map(|term| term.trim().to_string())
+            .filter(|term| !term.is_empty())
+    }
+
+    /// The exact traces a test is expected to emit, in order, as declared in its doc comments:
+    /// one per line. Tests declaring none aren't checked for traces. For example:
+    ///
+    ///   /// @log checking signatories
+    ///   /// @log checking validity range
+    ///   test foo() { ... }
+    ///
+    pub fn expected_logs(&self) -> Option<Vec<String>> {
+        let logs = self
+            .doc
+            .iter()
+            .flat_map(|doc| doc.lines())
+            .filter_map(|line| line.trim().strip_prefix("@log "))
+            .map(|log| log.trim().to_string())
+            .collect::<Vec<_>>();
+
+        if logs.is_empty() {
+            None
+        } else {
+            Some(logs)
+        }
+    }
+
+    /// Choices toward which counterexamples of a property are shrunk, instead of zero, as
+    /// declared in its doc comments: one byte per choice. This is useful when failures cluster
+    /// near some limit. For example:
+    ///
+    ///   /// @shrink_toward 255
+    ///   test foo(n via int()) { ... }
+    ///
+    /// Malformed declarations are ignored.
+    pub fn shrink_target(&self) -> Vec<u8> {
+        self.doc
+            .iter()
+            .flat_map(|doc| doc.lines())
+            .find_map(|line| line.trim().strip_prefix("@shrink_toward "))
+            .and_then(|target| {
+                target
+                    .split_whitespace()
+                    .map(|choice| choice.parse::<u8>().ok())
+                    .collect::<Option<Vec<_>>>()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn find_node(&self, byte_index: usize) -> Option<Located<'_>> {
         self.arguments
             .iter()
@@ -398,6 +496,25 @@ impl TypedDataType {
         }
     }
 
+    pub fn approx() -> Self {
+        DataType {
+            constructors: vec![RecordConstructor {
+                location: Span::empty(),
+                name: "Approx".to_string(),
+                arguments: vec![],
+                doc: None,
+                sugar: false,
+            }],
+            doc: None,
+            location: Span::empty(),
+            name: "Approx".to_string(),
+            opaque: false,
+            parameters: vec![],
+            public: true,
+            typed_parameters: vec![],
+        }
+    }
+
     pub fn ordering() -> Self {
         DataType {
             constructors: vec![
@@ -30,6 +30,7 @@ pub const ORDERING: &str = "Ordering";
 pub const REDEEMER_WRAPPER: &str = "RedeemerWrapper";
 pub const PRNG: &str = "PRNG";
 pub const FUZZER: &str = "Fuzzer";
+pub const APPROX: &str = "Approx";
 
 /// Build a prelude that can be injected
 /// into a compiler pipeline
@@ -419,8 +420,8 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
     // PRNG
     //
     // pub type PRNG {
-    //   Seeded { seed: ByteArray, choices: ByteArray }
-    //   Replayed { cursor: Int, choices: ByteArray }
+    //   Seeded { seed: ByteArray, choices: ByteArray, size: Int }
+    //   Replayed { cursor: Int, choices: ByteArray, size: Int }
     // }
 
     prelude.types.insert(
@@ -442,19 +443,20 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
     let mut seeded_fields = HashMap::new();
     seeded_fields.insert("seed".to_string(), (0, Span::empty()));
     seeded_fields.insert("choices".to_string(), (1, Span::empty()));
+    seeded_fields.insert("size".to_string(), (2, Span::empty()));
     prelude.values.insert(
         "Seeded".to_string(),
         ValueConstructor::public(
-            function(vec![byte_array(), byte_array()], prng()),
+            function(vec![byte_array(), byte_array(), int()], prng()),
             ValueConstructorVariant::Record {
                 module: "".into(),
                 name: "Seeded".to_string(),
                 field_map: Some(FieldMap {
-                    arity: 2,
+                    arity: 3,
                     fields: seeded_fields,
                     is_function: false,
                 }),
-                arity: 2,
+                arity: 3,
                 location: Span::empty(),
                 constructors_count: 2,
             },
@@ -464,25 +466,70 @@ pub fn prelude(id_gen: &IdGenerator) -> TypeInfo {
     let mut replayed_fields = HashMap::new();
     replayed_fields.insert("cursor".to_string(), (0, Span::empty()));
     replayed_fields.insert("choices".to_string(), (1, Span::empty()));
+    replayed_fields.insert("size".to_string(), (2, Span::empty()));
     prelude.values.insert(
         "Replayed".to_string(),
         ValueConstructor::public(
-            function(vec![int(), byte_array()], prng()),
+            function(vec![int(), byte_array(), int()], prng()),
             ValueConstructorVariant::Record {
                 module: "".into(),
                 name: "Replayed".to_string(),
                 field_map: Some(FieldMap {
-                    arity: 2,
+                    arity: 3,
                     fields: replayed_fields,
                     is_function: false,
                 }),
-                arity: 2,
+                arity: 3,
                 location: Span::empty(),
                 constructors_count: 2,
             },
         ),
     );
 
+    // Approx
+    //
+    // pub type Approx {
+    //   Approx { actual: Int, expected: Int, tolerance: Int }
+    // }
+
+    prelude.types.insert(
+        APPROX.to_string(),
+        TypeConstructor {
+            location: Span::empty(),
+            parameters: vec![],
+            tipo: approx(),
+            module: "".to_string(),
+            public: true,
+        },
+    );
+
+    prelude
+        .types_constructors
+        .insert(APPROX.to_string(), vec![APPROX.to_string()]);
+
+    let mut approx_fields = HashMap::new();
+    approx_fields.insert("actual".to_string(), (0, Span::empty()));
+    approx_fields.insert("expected".to_string(), (1, Span::empty()));
+    approx_fields.insert("tolerance".to_string(), (2, Span::empty()));
+    prelude.values.insert(
+        APPROX.to_string(),
+        ValueConstructor::public(
+            function(vec![int(), int(), int()], approx()),
+            ValueConstructorVariant::Record {
+                module: "".into(),
+                name: APPROX.to_string(),
+                field_map: Some(FieldMap {
+                    arity: 3,
+                    fields: approx_fields,
+                    is_function: false,
+                }),
+                arity: 3,
+                location: Span::empty(),
+                constructors_count: 1,
+            },
+        ),
+    );
+
     // Fuzzer
     //
     // pub type Fuzzer<a> =
@@ -1261,6 +1308,16 @@ pub fn prelude_data_types(id_gen: &IdGenerator) -> IndexMap<DataTypeKey, TypedDa
         prng_data_type,
     );
 
+    // Approx
+    let approx_data_type = TypedDataType::approx();
+    data_types.insert(
+        DataTypeKey {
+            module_name: "".to_string(),
+            defined_type: "Approx".to_string(),
+        },
+        approx_data_type,
+    );
+
     data_types
 }
 
@@ -1356,6 +1413,17 @@ pub fn prng() -> Rc<Type> {
     })
 }
 
+pub fn approx() -> Rc<Type> {
+    Rc::new(Type::App {
+        args: vec![],
+        public: true,
+        contains_opaque: false,
+        name: APPROX.to_string(),
+        module: "".to_string(),
+        alias: None,
+    })
+}
+
 pub fn fuzzer(a: Rc<Type>) -> Rc<Type> {
     let prng_annotation = Annotation::Constructor {
         location: Span::empty(),
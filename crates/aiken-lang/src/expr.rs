@@ -14,6 +14,7 @@ use crate::{
     },
 };
 use indexmap::IndexMap;
+use num_bigint::BigInt;
 use pallas::ledger::primitives::alonzo::{Constr, PlutusData};
 use std::{fmt::Debug, rc::Rc};
 use uplc::{
@@ -847,7 +848,9 @@ impl UntypedExpr {
         )
     }
 
-    fn reify_blind(data: PlutusData) -> Self {
+    /// Reify some 'PlutusData' as-is, as if it were of type 'Data'. Works for any data, as a
+    /// last resort when the expected type is unknown or doesn't match the data.
+    pub fn reify_blind(data: PlutusData) -> Self {
         match data {
             PlutusData::BigInt(ref i) => UntypedExpr::UInt {
                 location: Span::empty(),
@@ -1101,6 +1104,256 @@ impl UntypedExpr {
         )
     }
 
+    // Convert a literal 'UntypedExpr' back into 'PlutusData', using a Type annotation. This is the
+    // inverse of 'reify_data', and only supports expressions that 'reify_data' can produce:
+    // literals, lists, tuples and (fully applied) record or enum constructors.
+    pub fn to_data(
+        &self,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+        tipo: &Type,
+    ) -> Result<PlutusData, String> {
+        self.do_to_data(&mut IndexMap::new(), data_types, tipo)
+    }
+
+    fn do_to_data(
+        &self,
+        generics: &mut IndexMap<u64, Rc<Type>>,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+        tipo: &Type,
+    ) -> Result<PlutusData, String> {
+        if let Type::Var { tipo: var_tipo, .. } = tipo {
+            match &*var_tipo.borrow() {
+                TypeVar::Link { tipo } => {
+                    return self.do_to_data(generics, data_types, tipo);
+                }
+                TypeVar::Generic { id } => {
+                    if let Some(tipo) = generics.get(id) {
+                        return self.do_to_data(generics, data_types, &tipo.clone());
+                    }
+                }
+                _ => unreachable!("unbound type during conversion {tipo:?} -> {self:?}"),
+            }
+        }
+
+        if let Type::App { name, module, .. } = tipo {
+            if module.is_empty() && name == "Data" {
+                return self.to_data_blind();
+            }
+        }
+
+        // NOTE: Opaque types with a single constructor and a single field leave no residue in
+        // their PlutusData form. See 'reify_with'.
+        if check_replaceable_opaque_type(tipo, data_types) {
+            let inner_type = convert_opaque_type(&tipo.clone().into(), data_types, false);
+
+            return match self {
+                UntypedExpr::Call { arguments, .. } if arguments.len() == 1 => arguments[0]
+                    .value
+                    .do_to_data(generics, data_types, &inner_type),
+                _ => Err(format!("invalid value for opaque type {tipo:?}: {self:?}")),
+            };
+        }
+
+        match self {
+            UntypedExpr::UInt { .. } | UntypedExpr::UnOp { .. } if tipo.is_int() => {
+                self.to_data_blind()
+            }
+
+            UntypedExpr::ByteArray { .. } if tipo.is_bytearray() => self.to_data_blind(),
+
+            UntypedExpr::List {
+                elements,
+                tail: None,
+                ..
+            } => match tipo {
+                Type::App {
+                    module,
+                    name,
+                    args: type_args,
+                    ..
+                } if module.is_empty() && name.as_str() == "List" => {
+                    let [inner] = &type_args[..] else {
+                        return Err(
+                            "invalid List type annotation: the list has multiple type-parameters."
+                                .to_string(),
+                        );
+                    };
+
+                    let elements = elements
+                        .iter()
+                        .map(|elem| elem.do_to_data(generics, data_types, inner))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    if tipo.is_map() {
+                        elements
+                            .into_iter()
+                            .map(|elem| match elem {
+                                PlutusData::Array(kv) if kv.len() == 2 => {
+                                    let mut kv = kv.into_iter();
+                                    Ok((kv.next().unwrap(), kv.next().unwrap()))
+                                }
+                                _ => Err(format!("invalid map entry: {elem:?}")),
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                            .map(Data::map)
+                    } else {
+                        Ok(Data::list(elements))
+                    }
+                }
+                _ => Err(format!(
+                    "invalid type annotation. expected List but got: {tipo:?}"
+                )),
+            },
+
+            UntypedExpr::Tuple { elems, .. } => match tipo {
+                Type::Tuple {
+                    elems: elem_types, ..
+                } if elems.len() == elem_types.len() => elems
+                    .iter()
+                    .zip(elem_types)
+                    .map(|(elem, elem_type)| elem.do_to_data(generics, data_types, elem_type))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Data::list),
+                _ => Err(format!(
+                    "invalid type annotation. expected Tuple but got: {tipo:?}"
+                )),
+            },
+
+            UntypedExpr::Var { name, .. } => {
+                Self::constructor_to_data(generics, data_types, name, &[], tipo)
+            }
+
+            UntypedExpr::Call { fun, arguments, .. } => match fun.as_ref() {
+                UntypedExpr::Var { name, .. } => {
+                    Self::constructor_to_data(generics, data_types, name, arguments, tipo)
+                }
+                _ => Err(format!("unsupported constructor: {fun:?}")),
+            },
+
+            _ => Err(format!(
+                "unsupported value for conversion to data: {self:?}"
+            )),
+        }
+    }
+
+    fn constructor_to_data(
+        generics: &mut IndexMap<u64, Rc<Type>>,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+        name: &str,
+        arguments: &[CallArg<Self>],
+        tipo: &Type,
+    ) -> Result<PlutusData, String> {
+        let (Type::App { args, .. }, Some(data_type)) =
+            (tipo, lookup_data_type_by_tipo(data_types, tipo))
+        else {
+            return Err(format!(
+                "invalid type annotation {tipo:?} for constructor: {name}"
+            ));
+        };
+
+        let Some((ix, constructor)) = data_type
+            .constructors
+            .iter()
+            .enumerate()
+            .find(|(_, constructor)| constructor.name == name)
+        else {
+            return Err(format!("unknown constructor {name} for type {tipo:?}"));
+        };
+
+        if arguments.len() != constructor.arguments.len() {
+            return Err(format!(
+                "constructor {name} expects {} argument(s) but got {}",
+                constructor.arguments.len(),
+                arguments.len()
+            ));
+        }
+
+        data_type
+            .typed_parameters
+            .iter()
+            .zip(args)
+            .for_each(|(generic, arg)| {
+                if let Some(ix) = generic.get_generic() {
+                    if !generics.contains_key(&ix) {
+                        generics.insert(ix, arg.clone());
+                    }
+                }
+            });
+
+        let fields = constructor
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(position, RecordConstructorArg { label, tipo, .. })| {
+                let argument = match label {
+                    Some(label) => arguments
+                        .iter()
+                        .find(|arg| arg.label.as_ref() == Some(label))
+                        .or_else(|| arguments.get(position)),
+                    None => arguments.get(position),
+                }
+                .ok_or_else(|| format!("missing argument for constructor {name}"))?;
+
+                argument.value.do_to_data(generics, data_types, tipo)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Data::constr(ix as u64, fields))
+    }
+
+    // Inverse of 'reify_blind', for values of type 'Data'.
+    fn to_data_blind(&self) -> Result<PlutusData, String> {
+        match self {
+            UntypedExpr::UInt { value, .. } => BigInt::parse_bytes(value.as_bytes(), 10)
+                .map(Data::integer)
+                .ok_or_else(|| format!("invalid integer literal: {value}")),
+
+            UntypedExpr::UnOp {
+                op: UnOp::Negate,
+                value,
+                ..
+            } => match value.to_data_blind()? {
+                PlutusData::BigInt(i) => Ok(Data::integer(-from_pallas_bigint(&i))),
+                _ => Err(format!("cannot negate non-integer value: {value:?}")),
+            },
+
+            UntypedExpr::ByteArray { bytes, .. } => Ok(Data::bytestring(bytes.clone())),
+
+            UntypedExpr::List {
+                elements,
+                tail: None,
+                ..
+            } => elements
+                .iter()
+                .map(UntypedExpr::to_data_blind)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Data::list),
+
+            UntypedExpr::Call { fun, arguments, .. } if matches!(fun.as_ref(), UntypedExpr::Var { name, .. } if name == "Constr") =>
+            {
+                let (ix, fields) = arguments
+                    .split_first()
+                    .ok_or_else(|| "missing constructor index".to_string())?;
+
+                let ix = match ix.value.to_data_blind()? {
+                    PlutusData::BigInt(i) => u64::try_from(from_pallas_bigint(&i))
+                        .map_err(|_| "invalid constructor index".to_string())?,
+                    _ => return Err("invalid constructor index".to_string()),
+                };
+
+                fields
+                    .iter()
+                    .map(|field| field.value.to_data_blind())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(|fields| Data::constr(ix, fields))
+            }
+
+            _ => Err(format!(
+                "unsupported value for conversion to data: {self:?}"
+            )),
+        }
+    }
+
     pub fn todo(reason: Option<Self>, location: Span) -> Self {
         UntypedExpr::Trace {
             location,
@@ -20,10 +20,26 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
         .then(select! {Token::Name {name} => name})
         .then(
             via()
+                .map(Ok)
+                .or(missing_via().map(Err))
                 .separated_by(just(Token::Comma))
                 .allow_trailing()
                 .delimited_by(just(Token::LeftParen), just(Token::RightParen)),
         )
+        .validate(|((old_fail, name), arguments), _span, emit| {
+            let arguments = arguments
+                .into_iter()
+                .filter_map(|argument| match argument {
+                    Ok(argument) => Some(argument),
+                    Err(location) => {
+                        emit(ParseError::missing_fuzzer(name.clone(), location));
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            ((old_fail, name), arguments)
+        })
         .then(just(Token::Fail).ignored().or_not())
         .map_with_span(|name, span| (name, span))
         .then(
@@ -41,7 +57,7 @@ pub fn parser() -> impl Parser<Token, ast::UntypedDefinition, Error = ParseError
                     end_position: span.end - 1,
                     name,
                     public: false,
-                    return_annotation: Some(ast::Annotation::boolean(span)),
+                    return_annotation: None,
                     return_type: (),
                     can_error: fail.is_some() || old_fail.is_some(),
                 })
@@ -80,6 +96,17 @@ pub fn via() -> impl Parser<Token, ast::UntypedArgVia, Error = ParseError> {
     })
 }
 
+/// A test argument without any 'via' clause. Such arguments are never valid, but we parse
+/// them anyway to report a friendlier error than an unexpected token.
+fn missing_via() -> impl Parser<Token, ast::Span, Error = ParseError> {
+    select! {
+        Token::DiscardName {name} => name,
+        Token::Name {name} => name,
+    }
+    .then(just(Token::Colon).ignore_then(annotation()).or_not())
+    .map_with_span(|_, location| location)
+}
+
 pub fn fuzzer<'a>() -> impl Parser<Token, UntypedExpr, Error = ParseError> + 'a {
     recursive(|expression| {
         let chain = choice((
@@ -123,7 +150,11 @@ pub fn fuzzer<'a>() -> impl Parser<Token, UntypedExpr, Error = ParseError> + 'a
 
 #[cfg(test)]
 mod tests {
-    use crate::assert_definition;
+    use crate::{
+        assert_definition,
+        ast::ModuleKind,
+        parser::{self, error::ErrorKind},
+    };
 
     #[test]
     fn def_test() {
@@ -181,4 +212,22 @@ mod tests {
             "#
         );
     }
+
+    #[test]
+    fn def_property_test_missing_via() {
+        let errors = parser::module(
+            indoc::indoc! {r#"
+                test foo(x: Int) {
+                    True
+                }
+            "#},
+            ModuleKind::Lib,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            &errors[..],
+            [error] if matches!(&error.kind, ErrorKind::MissingFuzzer { test } if test == "foo")
+        ));
+    }
 }
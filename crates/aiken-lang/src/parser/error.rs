@@ -59,6 +59,16 @@ impl ParseError {
         }
     }
 
+    pub fn missing_fuzzer(test: String, span: Span) -> Self {
+        Self {
+            kind: ErrorKind::MissingFuzzer { test },
+            span,
+            while_parsing: None,
+            expected: HashSet::new(),
+            label: Some("missing fuzzer"),
+        }
+    }
+
     pub fn point_not_on_curve(curve: CurveType, span: Span) -> Self {
         Self {
             kind: ErrorKind::PointNotOnCurve { curve },
@@ -183,6 +193,22 @@ pub enum ErrorKind {
     )]
     UnfinishedAssignmentRightHandSide,
 
+    #[error("I found a test argument without any fuzzer in '{test}'.")]
+    #[diagnostic(help("{}", formatdoc! {
+        r#"Arguments of property tests are generated by fuzzers, which must be provided using the {keyword_via} keyword.
+
+           For example:
+
+             ┍━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+             │ {keyword_test} {test}(n {keyword_via} fuzz.int()) {{
+             │   ...
+             │ }}
+        "#,
+        keyword_test = "test".if_supports_color(Stdout, |s| s.bright_blue()),
+        keyword_via = "via".if_supports_color(Stdout, |s| s.yellow()),
+    }))]
+    MissingFuzzer { test: String },
+
     #[error("I tripped over a {}", fmt_curve_type(.curve))]
     PointNotOnCurve { curve: CurveType },
 
@@ -1678,6 +1678,56 @@ fn fuzzer_ok_list() {
     assert!(check(parse(source_code)).is_ok());
 }
 
+#[test]
+fn test_returns_approx() {
+    let source_code = r#"
+        test foo() {
+          Approx { actual: 10 / 3, expected: 3, tolerance: 1 }
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn test_returns_conjunction() {
+    let source_code = r#"
+        test foo() {
+          [("positive", 1 > 0), ("even", 2 % 2 == 0)]
+        }
+    "#;
+
+    assert!(check(parse(source_code)).is_ok());
+}
+
+#[test]
+fn test_returns_list_of_non_boolean() {
+    let source_code = r#"
+        test foo() {
+          [("positive", 1)]
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ));
+}
+
+#[test]
+fn test_returns_non_boolean() {
+    let source_code = r#"
+        test foo() {
+          42
+        }
+    "#;
+
+    assert!(matches!(
+        check(parse(source_code)),
+        Err((_, Error::CouldNotUnify { .. }))
+    ));
+}
+
 #[test]
 fn fuzzer_err_unbound() {
     let source_code = r#"
@@ -265,6 +265,27 @@ impl Type {
         }
     }
 
+    pub fn is_approx(&self) -> bool {
+        match self {
+            Self::App { module, name, .. } if "Approx" == name && module.is_empty() => true,
+            Self::Var { tipo, .. } => tipo.borrow().is_approx(),
+            _ => false,
+        }
+    }
+
+    /// Whether this is a labelled conjunction, 'List<(ByteArray, Bool)>', which tests may return
+    /// to check several things at once.
+    pub fn is_conjunction(&self) -> bool {
+        self.is_list()
+            && match &self.get_inner_types()[..] {
+                [elem] if elem.is_tuple() => matches!(
+                    &elem.get_inner_types()[..],
+                    [label, check] if label.is_bytearray() && check.is_bool()
+                ),
+                _ => false,
+            }
+    }
+
     pub fn is_int(&self) -> bool {
         match self {
             Self::App { module, name, .. } if "Int" == name && module.is_empty() => true,
@@ -882,6 +903,13 @@ impl TypeVar {
         }
     }
 
+    pub fn is_approx(&self) -> bool {
+        match self {
+            Self::Link { tipo } => tipo.is_approx(),
+            _ => false,
+        }
+    }
+
     pub fn is_int(&self) -> bool {
         match self {
             Self::Link { tipo } => tipo.is_int(),
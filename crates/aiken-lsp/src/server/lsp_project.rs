@@ -1,7 +1,10 @@
 use aiken_lang::{ast::Tracing, line_numbers::LineNumbers};
 use aiken_project::{
-    config::Config, error::Error as ProjectError, module::CheckedModule,
-    test_framework::PropertyTest, Project,
+    config::Config,
+    error::Error as ProjectError,
+    module::CheckedModule,
+    test_framework::{PropertyTest, RunSeed},
+    Project,
 };
 use std::{collections::HashMap, path::PathBuf};
 
@@ -37,8 +40,11 @@ impl LspProject {
             None,
             false,
             false,
-            u32::default(),
+            RunSeed::Fixed(u32::default()),
             PropertyTest::DEFAULT_MAX_SUCCESS,
+            None,
+            false,
+            false,
             Tracing::silent(),
         );
 
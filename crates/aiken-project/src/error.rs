@@ -87,12 +87,36 @@ pub enum Error {
         error: tipo::error::Error,
     },
 
-    #[error("{name} failed{}", if *verbose { format!("\n{src}") } else { String::new() } )]
+    #[error(
+        "{name} failed{}{}{}{}",
+        if *verbose { format!("\n{src}") } else { String::new() },
+        match (suite_seed, seed) {
+            (Some(suite_seed), Some(..)) => format!(
+                "\nseed: {suite_seed} (re-run it alone with: aiken check --seed {suite_seed} --match-tests \"{module}.{{{name}}}\" --exact-match)"
+            ),
+            (None, Some(seed)) => format!("\nseed: {seed} (its own seed, as given to 'PropertyTest::run')"),
+            _ => String::new(),
+        },
+        if *verbose && !choices.is_empty() { format!("\nchoices: {choices:?}") } else { String::new() },
+        if *verbose && *invalid_replays > 0 { format!("\ninvalid replays: {invalid_replays}") } else { String::new() },
+    )]
     TestFailure {
         name: String,
+        module: String,
         path: PathBuf,
         verbose: bool,
         src: String,
+        /// The seed a failing property ran from; that is, its own seed as derived from the
+        /// suite's (see 'RunSeed::test_seed'). Running the property from it yields the same
+        /// counterexample back.
+        seed: Option<u32>,
+        /// The seed of the suite the property ran in, if any. This is the one to give to
+        /// 'aiken check --seed' in order to reproduce the failure.
+        suite_seed: Option<u32>,
+        /// The choices the counterexample of a failing property was generated from, once shrunk.
+        choices: Vec<u8>,
+        /// Candidate choices the fuzzer couldn't replay while shrinking that counterexample.
+        invalid_replays: usize,
     },
 
     #[error(
@@ -127,8 +151,9 @@ pub enum Error {
 
 impl Error {
     pub fn report(&self) {
-        if let Error::TestFailure { verbose, .. } = self {
-            if !verbose {
+        // Failing properties are reported regardless, so that they can be reproduced.
+        if let Error::TestFailure { verbose, seed, .. } = self {
+            if !verbose && seed.is_none() {
                 return;
             }
         }
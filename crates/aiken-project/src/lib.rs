@@ -39,7 +39,7 @@ use aiken_lang::{
     expr::UntypedExpr,
     gen_uplc::CodeGenerator,
     line_numbers::LineNumbers,
-    tipo::{Type, TypeInfo},
+    tipo::TypeInfo,
     IdGenerator,
 };
 use export::Export;
@@ -57,12 +57,12 @@ use std::{
     fs::{self, File},
     io::BufReader,
     path::{Path, PathBuf},
-    rc::Rc,
+    time::{Duration, Instant},
 };
 use telemetry::EventListener;
-use test_framework::{Test, TestResult};
+use test_framework::{RunSeed, RunSummary, Test, TestResult};
 use uplc::{
-    ast::{Constant, Name, Program},
+    ast::{Name, Program},
     PlutusData,
 };
 
@@ -236,8 +236,11 @@ where
         match_tests: Option<Vec<String>>,
         verbose: bool,
         exact_match: bool,
-        seed: u32,
+        seed: RunSeed,
         property_max_success: usize,
+        suite_timeout: Option<Duration>,
+        raw_counterexamples: bool,
+        shuffle: bool,
         tracing: Tracing,
     ) -> Result<(), Vec<Error>> {
         let options = Options {
@@ -251,6 +254,9 @@ where
                     exact_match,
                     seed,
                     property_max_success,
+                    suite_timeout,
+                    raw_counterexamples,
+                    shuffle,
                 }
             },
         };
@@ -336,6 +342,9 @@ where
                 exact_match,
                 seed,
                 property_max_success,
+                suite_timeout,
+                raw_counterexamples,
+                shuffle,
             } => {
                 let tests =
                     self.collect_tests(verbose, match_tests, exact_match, options.tracing)?;
@@ -344,7 +353,22 @@ where
                     self.event_listener.handle_event(Event::RunningTests);
                 }
 
-                let tests = self.run_tests(tests, seed, property_max_success);
+                let random_seed = seed.is_random();
+
+                let seed = seed.resolve();
+
+                let start = Instant::now();
+
+                let tests = self.run_tests(
+                    tests,
+                    seed,
+                    property_max_success,
+                    suite_timeout,
+                    raw_counterexamples,
+                    shuffle,
+                );
+
+                let duration = start.elapsed();
 
                 self.checks_count = if tests.is_empty() {
                     None
@@ -352,7 +376,8 @@ where
                     Some(tests.iter().fold(0, |acc, test| {
                         acc + match test {
                             TestResult::PropertyTestResult(r) => r.iterations,
-                            _ => 1,
+                            TestResult::UnitTestResult(..) => 1,
+                            TestResult::Skipped(..) => 0,
                         }
                     }))
                 };
@@ -363,13 +388,31 @@ where
                         if e.is_success() {
                             None
                         } else {
-                            Some(e.into_error(verbose))
+                            Some(e.into_error(verbose, Some(seed)))
                         }
                     })
                     .collect();
 
-                self.event_listener
-                    .handle_event(Event::FinishedTests { seed, tests });
+                let summary = if verbose
+                    && tests
+                        .iter()
+                        .any(|t| matches!(t, TestResult::PropertyTestResult(..)))
+                {
+                    Some(RunSummary::new(&tests, duration))
+                } else {
+                    None
+                };
+
+                self.event_listener.handle_event(Event::FinishedTests {
+                    seed,
+                    random_seed,
+                    tests,
+                });
+
+                if let Some(summary) = summary {
+                    self.event_listener
+                        .handle_event(Event::SummarizedTests { summary });
+                }
 
                 if !errors.is_empty() {
                     Err(errors)
@@ -739,6 +782,11 @@ where
         exact_match: bool,
         tracing: Tracing,
     ) -> Result<Vec<Test>, Error> {
+        // Tests may be annotated with tags through their doc comments.
+        self.checked_modules.values_mut().for_each(|m| {
+            m.attach_doc_and_module_comments();
+        });
+
         let mut scripts = Vec::new();
 
         let match_tests = match_tests.map(|mt| {
@@ -834,24 +882,56 @@ where
 
     fn run_tests(
         &self,
-        tests: Vec<Test>,
+        mut tests: Vec<Test>,
         seed: u32,
         property_max_success: usize,
+        suite_timeout: Option<Duration>,
+        raw_counterexamples: bool,
+        shuffle: bool,
     ) -> Vec<TestResult<UntypedExpr, UntypedExpr>> {
-        use rayon::prelude::*;
-
         let data_types = utils::indexmap::as_ref_values(&self.data_types);
 
-        tests
-            .into_par_iter()
-            .map(|test| match test {
-                Test::UnitTest(unit_test) => unit_test.run(),
-                Test::PropertyTest(property_test) => property_test.run(seed, property_max_success),
-            })
-            .collect::<Vec<TestResult<(Constant, Rc<Type>), PlutusData>>>()
-            .into_iter()
-            .map(|test| test.reify(&data_types))
-            .collect()
+        // Outcomes stream in order of completion; reports follow the order of collection.
+        let positions: HashMap<(String, String), usize> = tests
+            .iter()
+            .enumerate()
+            .map(|(ix, test)| ((test.module().to_string(), test.name().to_string()), ix))
+            .collect();
+
+        let position = |module: &str, name: &str| {
+            positions
+                .get(&(module.to_string(), name.to_string()))
+                .copied()
+                .unwrap_or(usize::MAX)
+        };
+
+        if shuffle {
+            self.event_listener
+                .handle_event(Event::ShufflingTests { seed });
+            test_framework::shuffle(&mut tests, seed);
+        }
+
+        let mut results = Vec::new();
+
+        for result in test_framework::stream_suite(tests, seed, property_max_success, suite_timeout)
+        {
+            self.event_listener.handle_event(Event::FinishedTest {
+                module: result.module().to_string(),
+                name: result.title().to_string(),
+                success: result.is_success(),
+                skipped: result.is_skipped(),
+            });
+
+            results.push(if raw_counterexamples {
+                result.reify_raw(&data_types)
+            } else {
+                result.reify(&data_types)
+            });
+        }
+
+        results.sort_by_key(|result| position(result.module(), result.title()));
+
+        results
     }
 
     fn aiken_files(&mut self, dir: &Path, kind: ModuleKind) -> Result<(), Error> {
@@ -1,4 +1,6 @@
+use crate::test_framework::RunSeed;
 use aiken_lang::ast::Tracing;
+use std::time::Duration;
 
 pub struct Options {
     pub code_gen_mode: CodeGenMode,
@@ -19,8 +21,11 @@ pub enum CodeGenMode {
         match_tests: Option<Vec<String>>,
         verbose: bool,
         exact_match: bool,
-        seed: u32,
+        seed: RunSeed,
         property_max_success: usize,
+        suite_timeout: Option<Duration>,
+        raw_counterexamples: bool,
+        shuffle: bool,
     },
     Build(bool),
     NoOp,
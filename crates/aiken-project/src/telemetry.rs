@@ -1,6 +1,9 @@
 use crate::{
     pretty,
-    test_framework::{PropertyTestResult, TestResult, UnitTestResult},
+    test_framework::{
+        Finding, GoldenMismatch, LogsDiff, PropertyTestResult, RunSummary, SkippedTest, TestResult,
+        UnitTestResult,
+    },
 };
 use aiken_lang::{expr::UntypedExpr, format::Formatter};
 use owo_colors::{OwoColorize, Stream::Stderr};
@@ -36,10 +39,29 @@ pub enum Event {
         path: PathBuf,
     },
     RunningTests,
+    /// Tests are about to run in an order derived from the given seed.
+    ShufflingTests {
+        seed: u32,
+    },
+    /// A single test of the suite completed, or was skipped. Emitted as tests complete, in no
+    /// particular order, and before 'FinishedTests' reports them all.
+    FinishedTest {
+        module: String,
+        name: String,
+        success: bool,
+        skipped: bool,
+    },
     FinishedTests {
         seed: u32,
+        /// Whether the seed was picked at random, and must therefore be reported for the run to
+        /// be reproducible.
+        random_seed: bool,
         tests: Vec<TestResult<UntypedExpr, UntypedExpr>>,
     },
+    /// Aggregates over a suite that ran property tests, only reported when verbose.
+    SummarizedTests {
+        summary: RunSummary,
+    },
     WaitingForBuildDirLock,
     ResolvingPackages {
         name: String,
@@ -171,7 +193,62 @@ impl EventListener for Terminal {
                     "...".if_supports_color(Stderr, |s| s.bold())
                 );
             }
-            Event::FinishedTests { seed, tests } => {
+            Event::ShufflingTests { seed } => {
+                eprintln!(
+                    "{} tests with {opt}={seed}\n",
+                    "    Shuffling"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    opt = "--seed".if_supports_color(Stderr, |s| s.bold()),
+                    seed = format!("{seed}").if_supports_color(Stderr, |s| s.bold())
+                );
+            }
+            // Only a progress line; details are reported all at once, grouped by module, when
+            // the suite is over.
+            Event::FinishedTest {
+                module,
+                name,
+                success,
+                skipped,
+            } => {
+                let status = if skipped {
+                    "SKIP"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.yellow())
+                        .to_string()
+                } else if success {
+                    "PASS"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.green())
+                        .to_string()
+                } else {
+                    "FAIL"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.red())
+                        .to_string()
+                };
+
+                eprintln!(
+                    "         {status} {}.{{{}}}",
+                    module.if_supports_color(Stderr, |s| s.blue()),
+                    name.if_supports_color(Stderr, |s| s.bright_blue()),
+                );
+            }
+            Event::FinishedTests {
+                seed,
+                random_seed,
+                tests,
+            } => {
+                // Set the results apart from the progress lines above.
+                if !tests.is_empty() {
+                    eprintln!();
+                }
+
+                // Skipped tests have nothing to show but why they were skipped; they're listed
+                // on their own, after all others.
+                let (skipped, tests): (Vec<_>, Vec<_>) =
+                    tests.into_iter().partition(TestResult::is_skipped);
+
                 let (max_mem, max_cpu, max_iter) = find_max_execution_units(&tests);
 
                 for (module, results) in &group_by_module(&tests) {
@@ -210,6 +287,56 @@ impl EventListener for Terminal {
                         )
                     );
                 }
+
+                // Random seeds are reported regardless of the outcome, but failures are only
+                // reproducible with it; hence the nudge.
+                if random_seed {
+                    let failed = tests.iter().any(|t| !t.is_success());
+                    eprintln!(
+                        "{} {seed} (picked at random){}",
+                        "         Seed"
+                            .if_supports_color(Stderr, |s| s.bold())
+                            .if_supports_color(Stderr, |s| s.purple()),
+                        if failed {
+                            format!(
+                                "; re-run with {opt}={seed} to reproduce failures",
+                                opt = "--seed".if_supports_color(Stderr, |s| s.bold()),
+                            )
+                        } else {
+                            String::new()
+                        },
+                    );
+                }
+
+                for SkippedTest {
+                    module,
+                    name,
+                    reason,
+                } in skipped.into_iter().filter_map(|result| match result {
+                    TestResult::Skipped(test) => Some(test),
+                    TestResult::UnitTestResult(..) | TestResult::PropertyTestResult(..) => None,
+                }) {
+                    eprintln!(
+                        "{} {}.{{{}}} ({reason})",
+                        "      Skipped"
+                            .if_supports_color(Stderr, |s| s.bold())
+                            .if_supports_color(Stderr, |s| s.yellow()),
+                        module.if_supports_color(Stderr, |s| s.blue()),
+                        name.if_supports_color(Stderr, |s| s.bright_blue()),
+                    );
+                }
+            }
+            Event::SummarizedTests { summary } => {
+                eprintln!(
+                    "{} {} property iterations, {} shrink steps; {}ms in properties, {}ms in unit tests",
+                    "      Summary"
+                        .if_supports_color(Stderr, |s| s.bold())
+                        .if_supports_color(Stderr, |s| s.purple()),
+                    summary.property_iterations,
+                    summary.shrink_steps,
+                    summary.property_duration_ms,
+                    summary.unit_duration_ms,
+                );
             }
             Event::ResolvingPackages { name } => {
                 eprintln!(
@@ -319,6 +446,7 @@ fn fmt_test(
                 if *iterations > 1 { "s" } else { "" }
             );
         }
+        TestResult::Skipped(..) => {}
     }
 
     // Title
@@ -331,21 +459,126 @@ fn fmt_test(
 
     // Annotations
     match result {
+        TestResult::UnitTestResult(UnitTestResult {
+            non_boolean: Some(output),
+            ..
+        }) => {
+            test = format!(
+                "{test}\n{} {output}",
+                "× test did not return Bool, got:"
+                    .if_supports_color(Stderr, |s| s.red())
+                    .if_supports_color(Stderr, |s| s.bold()),
+            );
+        }
         TestResult::UnitTestResult(UnitTestResult {
             assertion: Some(assertion),
-            test: unit_test,
+            diff,
             ..
         }) if !result.is_success() => {
             test = format!(
                 "{test}\n{}",
-                assertion.to_string(Stderr, unit_test.can_error),
+                assertion.to_string(Stderr, result.can_error()),
             );
+
+            if !diff.is_empty() {
+                test = format!(
+                    "{test}\n{}\n{}",
+                    "× difference"
+                        .if_supports_color(Stderr, |s| s.red())
+                        .if_supports_color(Stderr, |s| s.bold()),
+                    diff.iter()
+                        .map(|d| format!("{} {d}", "│".if_supports_color(Stderr, |s| s.red())))
+                        .collect::<Vec<String>>()
+                        .join("\n")
+                );
+            }
         }
         _ => (),
     }
 
+    // Runtime error
+    if let TestResult::UnitTestResult(UnitTestResult {
+        runtime_error: Some(error),
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{} {error}",
+            "× test errored:"
+                .if_supports_color(Stderr, |s| s.red())
+                .if_supports_color(Stderr, |s| s.bold())
+        );
+    }
+
+    // Expected error
+    if let TestResult::UnitTestResult(UnitTestResult {
+        test: unit_test, ..
+    }) = result
+    {
+        if let Some(expected) = unit_test.expected_error.as_ref() {
+            if unit_test.can_error && !result.is_success() {
+                test = format!(
+                    "{test}\n{} {expected}",
+                    "× expected to fail with:"
+                        .if_supports_color(Stderr, |s| s.red())
+                        .if_supports_color(Stderr, |s| s.bold())
+                );
+            }
+        }
+    }
+
+    // Golden mismatch
+    if let TestResult::UnitTestResult(UnitTestResult {
+        golden_mismatch: Some(GoldenMismatch { expected, actual }),
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{}\n{} {expected}\n{} {actual}",
+            "× golden mismatch"
+                .if_supports_color(Stderr, |s| s.red())
+                .if_supports_color(Stderr, |s| s.bold()),
+            "- expected:".if_supports_color(Stderr, |s| s.red()),
+            "+ actual:  ".if_supports_color(Stderr, |s| s.green()),
+        );
+    }
+
+    // Logs mismatch
+    if let TestResult::UnitTestResult(UnitTestResult {
+        logs_mismatch: Some(mismatch),
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{}",
+            "× logs mismatch"
+                .if_supports_color(Stderr, |s| s.red())
+                .if_supports_color(Stderr, |s| s.bold()),
+        );
+
+        for line in mismatch.diff() {
+            test = match line {
+                LogsDiff::Same(log) => format!("{test}\n  {log}"),
+                LogsDiff::Missing(log) => format!(
+                    "{test}\n{}",
+                    format!("- {log}").if_supports_color(Stderr, |s| s.red())
+                ),
+                LogsDiff::Unexpected(log) => format!(
+                    "{test}\n{}",
+                    format!("+ {log}").if_supports_color(Stderr, |s| s.green())
+                ),
+            };
+        }
+    }
+
     // CounterExamples
-    if let TestResult::PropertyTestResult(PropertyTestResult { counterexample, .. }) = result {
+    if let TestResult::PropertyTestResult(property) = result {
+        let PropertyTestResult {
+            counterexample,
+            min_successful,
+            ..
+        } = property;
+
         match counterexample {
             Err(err) => {
                 test = format!(
@@ -357,8 +590,18 @@ fn fmt_test(
                 );
             }
 
+            Ok(None) if property.lacks_iterations() => {
+                test = format!(
+                    "{test}\n{}",
+                    format!("× property ran fewer than {min_successful} iteration(s)")
+                        .if_supports_color(Stderr, |s| s.red())
+                        .if_supports_color(Stderr, |s| s.bold())
+                );
+            }
+
             Ok(None) => {
-                if !result.is_success() {
+                // Other errors (e.g. an oversized input) are reported on their own, below.
+                if result.is_failure() {
                     test = format!(
                         "{test}\n{}",
                         "× no counterexample found"
@@ -368,44 +611,184 @@ fn fmt_test(
                 }
             }
 
-            Ok(Some(counterexample)) => {
-                let is_expected_failure = result.is_success();
+            Ok(Some(_)) => {
+                let is_expected_failure = result.is_expected_failure();
 
-                test = format!(
-                    "{test}\n{}\n{}",
-                    if is_expected_failure {
-                        "★ counterexample"
-                            .if_supports_color(Stderr, |s| s.green())
-                            .if_supports_color(Stderr, |s| s.bold())
-                            .to_string()
+                let finding = match property.finding() {
+                    Some(Finding::ExpectedFailure) => "expected failure",
+                    Some(Finding::Counterexample) | None => "counterexample",
+                };
+
+                let distinct = property.distinct_counterexamples();
+
+                for (ix, counterexample) in distinct.into_iter().enumerate() {
+                    let title = if ix == 0 {
+                        finding.to_string()
                     } else {
-                        "× counterexample"
-                            .if_supports_color(Stderr, |s| s.red())
-                            .if_supports_color(Stderr, |s| s.bold())
-                            .to_string()
-                    },
-                    &Formatter::new()
-                        .expr(counterexample, false)
-                        .to_pretty_string(60)
-                        .lines()
-                        .map(|line| {
-                            format!(
-                                "{} {}",
-                                "│".if_supports_color(Stderr, |s| if is_expected_failure {
-                                    s.green().to_string()
-                                } else {
-                                    s.red().to_string()
-                                }),
-                                line
-                            )
-                        })
-                        .collect::<Vec<String>>()
-                        .join("\n"),
+                        format!("{finding} #{}", ix + 1)
+                    };
+
+                    test = format!(
+                        "{test}\n{}\n{}",
+                        if is_expected_failure {
+                            format!("★ {title}")
+                                .if_supports_color(Stderr, |s| s.green())
+                                .if_supports_color(Stderr, |s| s.bold())
+                                .to_string()
+                        } else {
+                            format!("× {title}")
+                                .if_supports_color(Stderr, |s| s.red())
+                                .if_supports_color(Stderr, |s| s.bold())
+                                .to_string()
+                        },
+                        &Formatter::new()
+                            .expr(counterexample, false)
+                            .to_pretty_string(60)
+                            .lines()
+                            .map(|line| {
+                                format!(
+                                    "{} {}",
+                                    "│".if_supports_color(Stderr, |s| if is_expected_failure {
+                                        s.green().to_string()
+                                    } else {
+                                        s.red().to_string()
+                                    }),
+                                    line
+                                )
+                            })
+                            .collect::<Vec<String>>()
+                            .join("\n"),
+                    );
+                }
+
+                let shrinking = if property.was_shrunk() {
+                    format!(
+                        "· shrunk in {} steps, down to {} choices",
+                        property.shrink_stats.total(),
+                        property.choices.len()
+                    )
+                } else {
+                    format!("· {finding} could not be simplified further")
+                };
+
+                test = format!(
+                    "{test}\n{}",
+                    shrinking.if_supports_color(Stderr, |s| s.bold())
                 );
+
+                for line in &property.scenario {
+                    test = format!(
+                        "{test}\n{}",
+                        format!("· scenario: {line}").if_supports_color(Stderr, |s| s.bold())
+                    );
+                }
             }
         }
     }
 
+    // Non-determinism
+    if let TestResult::PropertyTestResult(PropertyTestResult {
+        nondeterministic: true,
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{}",
+            "× fuzzer is non-deterministic: replaying the counterexample's choices led elsewhere"
+                .if_supports_color(Stderr, |s| s.red())
+                .if_supports_color(Stderr, |s| s.bold())
+        );
+    }
+
+    // Failed check
+    if let TestResult::PropertyTestResult(PropertyTestResult {
+        failed_check: Some(label),
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{} {label}",
+            "× failing check:"
+                .if_supports_color(Stderr, |s| s.red())
+                .if_supports_color(Stderr, |s| s.bold())
+        );
+    }
+
+    // Oversized input
+    if let TestResult::PropertyTestResult(PropertyTestResult {
+        oversized_input: Some(choices),
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{}",
+            format!(
+                "× fuzzer generated too large an input ({choices} choices); check that recursive fuzzers reach their base case"
+            )
+            .if_supports_color(Stderr, |s| s.red())
+            .if_supports_color(Stderr, |s| s.bold())
+        );
+    }
+
+    // Evaluation error
+    if let TestResult::PropertyTestResult(PropertyTestResult {
+        eval_error: Some(error),
+        ..
+    }) = result
+    {
+        if !result.is_success() {
+            test = format!(
+                "{test}\n{} {error}",
+                "× property errored:"
+                    .if_supports_color(Stderr, |s| s.red())
+                    .if_supports_color(Stderr, |s| s.bold())
+            );
+        }
+    }
+
+    // Tracing divergence
+    if let TestResult::PropertyTestResult(PropertyTestResult {
+        untraced_divergence: true,
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{}",
+            "⚠ outcome differs when traces are disabled; the failure may be caused by traces"
+                .if_supports_color(Stderr, |s| s.yellow())
+                .if_supports_color(Stderr, |s| s.bold())
+        );
+    }
+
+    // Stalled fuzzer
+    if let TestResult::PropertyTestResult(PropertyTestResult {
+        stalled_fuzzer: true,
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{}",
+            "⚠ fuzzer draws values without advancing its seed; values drawn together are all alike"
+                .if_supports_color(Stderr, |s| s.yellow())
+                .if_supports_color(Stderr, |s| s.bold())
+        );
+    }
+
+    // Reduced coverage
+    if let TestResult::PropertyTestResult(PropertyTestResult {
+        reduced_from: Some(max_success),
+        iterations,
+        ..
+    }) = result
+    {
+        test = format!(
+            "{test}\n{}",
+            format!("⚠ reduced coverage: ran {iterations} of {max_success} iterations to fit the suite timeout")
+                .if_supports_color(Stderr, |s| s.yellow())
+                .if_supports_color(Stderr, |s| s.bold())
+        );
+    }
+
     // Labels
     if let TestResult::PropertyTestResult(PropertyTestResult { labels, .. }) = result {
         if !labels.is_empty() && result.is_success() {
@@ -504,6 +887,7 @@ fn find_max_execution_units<T>(xs: &[TestResult<T, T>]) -> (usize, usize, usize)
                         (max_mem, max_cpu, max_iter)
                     }
                 }
+                TestResult::Skipped(..) => (max_mem, max_cpu, max_iter),
             });
 
     (
@@ -512,3 +896,40 @@ fn find_max_execution_units<T>(xs: &[TestResult<T, T>]) -> (usize, usize, usize)
         max_iter.to_string().len(),
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_framework::Test;
+    use indoc::indoc;
+
+    #[test]
+    fn test_fmt_oversized_input() {
+        let prop = match Test::from_source(indoc! { r#"
+            fn zero() -> Fuzzer<Int> {
+              fn(prng) { Some((prng, 0)) }
+            }
+
+            test foo(n: Int via zero()) {
+              n == 0
+            }
+        "#})
+        {
+            (Test::PropertyTest(prop), _) => prop,
+            (Test::UnitTest(..), _) => {
+                panic!("Expected to yield a PropertyTest but found a UnitTest")
+            }
+        };
+
+        let result = TestResult::PropertyTestResult(PropertyTestResult {
+            oversized_input: Some(100_001),
+            ..PropertyTestResult::empty(prop, Some(42))
+        });
+
+        let rendered = fmt_test(&result, 0, 0, 0, false);
+
+        assert!(rendered.contains("× fuzzer generated too large an input (100001 choices)"));
+        assert!(!rendered.contains("fewer than"));
+        assert!(!rendered.contains("no counterexample found"));
+    }
+}
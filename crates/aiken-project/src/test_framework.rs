@@ -1,10 +1,10 @@
 use aiken_lang::{
     ast::{Arg, BinOp, DataTypeKey, IfBranch, Span, TypedDataType, TypedTest},
-    builtins::bool,
+    builtins::{bool, fuzzer},
     expr::{TypedExpr, UntypedExpr},
     format::Formatter,
     gen_uplc::CodeGenerator,
-    tipo::{convert_opaque_type, Type},
+    tipo::{convert_opaque_type, Type, TypeAliasAnnotation, TypeVar},
 };
 use cryptoxide::{blake2b::Blake2b, digest::Digest};
 use indexmap::IndexMap;
@@ -12,12 +12,31 @@ use itertools::Itertools;
 use owo_colors::{OwoColorize, Stream};
 use pallas::ledger::primitives::alonzo::{Constr, PlutusData};
 use patricia_tree::PatriciaMap;
+use rand::Rng;
 use std::{
-    borrow::Borrow, collections::BTreeMap, convert::TryFrom, ops::Deref, path::PathBuf, rc::Rc,
+    borrow::Borrow,
+    cell::RefCell,
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::{self, Display},
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
 };
 use uplc::{
-    ast::{Constant, Data, Name, NamedDeBruijn, Program, Term},
-    machine::{cost_model::ExBudget, eval_result::EvalResult},
+    ast::{Constant, Data, DeBruijn, Name, NamedDeBruijn, Program, Term},
+    builtins::DefaultFunction,
+    flat::Binder,
+    machine::{
+        cost_model::{CostModel, ExBudget},
+        eval_result::EvalResult,
+        value::Value,
+        ErrorKind,
+    },
 };
 use vec1::{vec1, Vec1};
 
@@ -44,8 +63,6 @@ pub enum Test {
     PropertyTest(PropertyTest),
 }
 
-unsafe impl Send for Test {}
-
 impl Test {
     pub fn unit_test(
         generator: &mut CodeGenerator<'_>,
@@ -53,8 +70,27 @@ impl Test {
         module_name: String,
         input_path: PathBuf,
     ) -> Test {
+        let tags = test.tags();
+
+        let expected_error = test.expected_error();
+
+        // Golden terms are compared as pretty-printed, so they needn't be written in canonical
+        // form. Those that don't parse are kept as-is, and simply won't match.
+        let golden = test.golden().map(|golden| {
+            uplc::parser::term(&golden)
+                .map(|term| term.to_pretty())
+                .unwrap_or(golden)
+        });
+
+        let expected_logs = test.expected_logs();
+
         let program = generator.generate_raw(&test.body, &[], &module_name);
 
+        let trivial = matches!(
+            &test.body,
+            TypedExpr::Var { name, constructor, .. } if name == "True" && constructor.tipo.is_bool()
+        );
+
         let assertion = match test.body.try_into() {
             Err(..) => None,
             Ok(Assertion { bin_op, head, tail }) => {
@@ -80,6 +116,8 @@ impl Test {
             }
         };
 
+        let trivial = trivial && assertion.is_none();
+
         Test::UnitTest(UnitTest {
             input_path,
             module: module_name,
@@ -87,9 +125,15 @@ impl Test {
             program,
             assertion,
             can_error: test.can_error,
+            expected_error,
+            golden,
+            expected_logs,
+            trivial,
+            tags,
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn property_test(
         input_path: PathBuf,
         module: String,
@@ -97,7 +141,11 @@ impl Test {
         can_error: bool,
         program: Program<Name>,
         fuzzer: Fuzzer<Name>,
+        tags: Vec<String>,
+        shrink_target: Vec<u8>,
     ) -> Test {
+        let compiled = Rc::new(Compiled::new(&program, &fuzzer.program));
+
         Test::PropertyTest(PropertyTest {
             input_path,
             module,
@@ -105,6 +153,9 @@ impl Test {
             program,
             can_error,
             fuzzer,
+            tags,
+            shrink_target,
+            compiled,
         })
     }
 
@@ -119,10 +170,21 @@ impl Test {
         } else {
             let parameter = test.arguments.first().unwrap().to_owned();
 
+            // Arguments without a 'via' clause never make it past the parser, which reports them
+            // as a missing fuzzer on the offending test.
             let via = parameter.via.clone();
 
             let type_info = parameter.tipo.clone();
 
+            // The type-checker makes sure fuzzers yield values of their argument's type; values
+            // would otherwise fail to reify, far from their cause.
+            debug_assert!(
+                unifies(&via.tipo(), &fuzzer(type_info.clone())),
+                "fuzzer of '{}' doesn't yield values of type {}",
+                test.name,
+                type_info.to_pretty(0),
+            );
+
             let stripped_type_info = convert_opaque_type(&type_info, generator.data_types(), true);
 
             let program = generator.clone().generate_raw(
@@ -139,6 +201,10 @@ impl Test {
             // apply onto it later.
             let fuzzer = generator.clone().generate_raw(&via, &[], &module_name);
 
+            let tags = test.tags();
+
+            let shrink_target = test.shrink_target();
+
             Self::property_test(
                 input_path,
                 module_name,
@@ -150,9 +216,476 @@ impl Test {
                     stripped_type_info,
                     type_info,
                 },
+                tags,
+                shrink_target,
             )
         }
     }
+
+    /// A deep copy of the test, sharing no reference-counted node with the original (nor with
+    /// anything else). This is what makes it safe to send to another thread; see 'Thread safety'.
+    /// Programs are copied through a flat-encoding round-trip.
+    fn detach(self) -> Detached<Test> {
+        fn program<'b, T: Binder<'b> + fmt::Debug>(bytes: &'b [u8]) -> Program<T> {
+            Program::from_flat(bytes).expect("failed to decode flat-encoded program")
+        }
+
+        fn flat<'b, T: Binder<'b> + fmt::Debug>(program: &Program<T>) -> Vec<u8> {
+            program.to_flat().expect("failed to flat-encode program")
+        }
+
+        Detached(match self {
+            Test::UnitTest(test) => Test::UnitTest(UnitTest {
+                program: program(&flat(&test.program)),
+                assertion: test.assertion.map(|Assertion { bin_op, head, tail }| {
+                    let detach = |(cst, tipo): (Constant, Rc<Type>)| {
+                        (detach_constant(&cst), detach_type(&tipo))
+                    };
+                    Assertion {
+                        bin_op,
+                        head: head.map(detach),
+                        tail: tail.map(|tail| tail.mapped(detach)),
+                    }
+                }),
+                ..test
+            }),
+            Test::PropertyTest(test) => Test::PropertyTest(PropertyTest {
+                program: program(&flat(&test.program)),
+                fuzzer: Fuzzer {
+                    program: program(&flat(&test.fuzzer.program)),
+                    type_info: detach_type(&test.fuzzer.type_info),
+                    stripped_type_info: detach_type(&test.fuzzer.stripped_type_info),
+                },
+                compiled: Rc::new(Compiled {
+                    property: program(&flat(&test.compiled.property)),
+                    untraced: program(&flat(&test.compiled.untraced)),
+                    fuzzer: program(&flat(&test.compiled.fuzzer)),
+                }),
+                ..test
+            }),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Test::UnitTest(UnitTest { name, .. })
+            | Test::PropertyTest(PropertyTest { name, .. }) => name,
+        }
+    }
+
+    pub fn module(&self) -> &str {
+        match self {
+            Test::UnitTest(UnitTest { module, .. })
+            | Test::PropertyTest(PropertyTest { module, .. }) => module,
+        }
+    }
+
+    pub fn input_path(&self) -> &Path {
+        match self {
+            Test::UnitTest(UnitTest { input_path, .. })
+            | Test::PropertyTest(PropertyTest { input_path, .. }) => input_path,
+        }
+    }
+
+    pub fn is_property(&self) -> bool {
+        matches!(self, Test::PropertyTest(..))
+    }
+
+    /// Tags the test was annotated with (see 'TypedTest::tags').
+    pub fn tags(&self) -> &[String] {
+        match self {
+            Test::UnitTest(UnitTest { tags, .. })
+            | Test::PropertyTest(PropertyTest { tags, .. }) => tags,
+        }
+    }
+
+    /// Whether the test was annotated with the given tag. Tags are given without their leading
+    /// '@'.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags().iter().any(|t| t == tag)
+    }
+
+    /// Run a test. Property tests each draw from their own stream, derived from the given seed
+    /// and the test's fully qualified name (see 'RunSeed::test_seed'); so that tests sharing a seed
+    /// do not explore correlated values.
+    pub fn run(
+        self,
+        seed: u32,
+        max_success: usize,
+    ) -> TestResult<(Constant, Rc<Type>), PlutusData> {
+        match self {
+            Test::UnitTest(unit_test) => unit_test.run(),
+            Test::PropertyTest(property_test) => {
+                let seed = RunSeed::test_seed(seed, &property_test.module, &property_test.name);
+                property_test.run(seed, max_success)
+            }
+        }
+    }
+
+    /// Describe how the test would run, without running it. Unit tests run exactly once,
+    /// whereas properties run up to 'max_success' times.
+    pub fn plan(&self, max_success: usize) -> PlannedTest {
+        PlannedTest {
+            module: self.module().to_string(),
+            name: self.name().to_string(),
+            is_property: self.is_property(),
+            iterations: if self.is_property() { max_success } else { 1 },
+        }
+    }
+
+    /// Run a test as part of a suite started at 'start', unless the suite timeout has elapsed.
+    /// As the timeout approaches, properties run fewer iterations (see 'reduced_iterations'), and
+    /// stop shrinking at the timeout; so that the suite finishes in time, with reduced coverage
+    /// rather than none.
+    fn run_in_suite(
+        self,
+        start: Instant,
+        seed: u32,
+        max_success: usize,
+        timeout: Option<Duration>,
+    ) -> TestResult<(Constant, Rc<Type>), PlutusData> {
+        let timeout = match timeout {
+            Some(timeout) if start.elapsed() >= timeout => {
+                return TestResult::Skipped(self.skip("suite timeout"));
+            }
+            Some(timeout) => timeout,
+            None => return self.run(seed, max_success),
+        };
+
+        match self {
+            Test::PropertyTest(property_test) => {
+                let elapsed = start.elapsed();
+
+                let n = match reduced_iterations(max_success, elapsed, timeout) {
+                    Some(n) => n,
+                    None => return Test::PropertyTest(property_test).run(seed, max_success),
+                };
+
+                let seed = RunSeed::test_seed(seed, &property_test.module, &property_test.name);
+
+                let options = PropertyTestOptions::new(seed)
+                    .max_success(n)
+                    .shrink_timeout(timeout.saturating_sub(elapsed));
+
+                match property_test.run_with(options) {
+                    TestResult::PropertyTestResult(result) => {
+                        TestResult::PropertyTestResult(PropertyTestResult {
+                            reduced_from: Some(max_success),
+                            ..result
+                        })
+                    }
+                    result => result,
+                }
+            }
+            test => test.run(seed, max_success),
+        }
+    }
+
+    fn skip(self, reason: &str) -> SkippedTest {
+        let (module, name) = match self {
+            Test::UnitTest(UnitTest { module, name, .. })
+            | Test::PropertyTest(PropertyTest { module, name, .. }) => (module, name),
+        };
+
+        SkippedTest {
+            module,
+            name,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Whether two types could unify: they have the same shape once links are followed, with type
+/// variables standing for anything. This is only a sanity check on already type-checked code, so
+/// it errs on the permissive side.
+fn unifies(left: &Type, right: &Type) -> bool {
+    let all = |lefts: &[Rc<Type>], rights: &[Rc<Type>]| {
+        lefts.len() == rights.len()
+            && lefts
+                .iter()
+                .zip(rights)
+                .all(|(left, right)| unifies(left, right))
+    };
+
+    match (left, right) {
+        (Type::Var { tipo, .. }, other) | (other, Type::Var { tipo, .. }) => {
+            match &*RefCell::borrow(tipo) {
+                TypeVar::Link { tipo } => unifies(tipo, other),
+                TypeVar::Unbound { .. } | TypeVar::Generic { .. } => true,
+            }
+        }
+        (
+            Type::App {
+                module, name, args, ..
+            },
+            Type::App {
+                module: module2,
+                name: name2,
+                args: args2,
+                ..
+            },
+        ) => module == module2 && name == name2 && all(args, args2),
+        (
+            Type::Fn { args, ret, .. },
+            Type::Fn {
+                args: args2,
+                ret: ret2,
+                ..
+            },
+        ) => unifies(ret, ret2) && all(args, args2),
+        (Type::Tuple { elems, .. }, Type::Tuple { elems: elems2, .. }) => all(elems, elems2),
+        _ => false,
+    }
+}
+
+/// Number of iterations a property started 'elapsed' into a suite should run so that the suite
+/// fits within 'timeout', if fewer than 'max_success'. Properties started in the first half of
+/// the suite run fully; past that, they run a number of iterations proportional to the time
+/// left, down to a single one right before the timeout.
+fn reduced_iterations(max_success: usize, elapsed: Duration, timeout: Duration) -> Option<usize> {
+    let remaining = timeout.saturating_sub(elapsed);
+
+    if remaining * 2 >= timeout {
+        return None;
+    }
+
+    let n = (max_success as u128 * remaining.as_nanos() * 2 / timeout.as_nanos().max(1)) as usize;
+
+    Some(n.max(1))
+}
+
+/// ----- RunSeed -----------------------------------------------------------------
+///
+/// How the base seed of a whole suite is chosen. Either way, the suite is seeded once and each
+/// property test derives its own seed from it (see 'RunSeed::test_seed'), so that any run can be
+/// reproduced from the base seed alone; provided it gets reported, which is why random seeds
+/// are settled on up-front rather than drawn as needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunSeed {
+    Fixed(u32),
+    Random,
+}
+
+impl RunSeed {
+    /// Settle on a base seed for the suite; random ones are sampled from the OS.
+    pub fn resolve(self) -> u32 {
+        match self {
+            RunSeed::Fixed(seed) => seed,
+            RunSeed::Random => rand::rngs::OsRng.gen(),
+        }
+    }
+
+    pub fn is_random(&self) -> bool {
+        matches!(self, RunSeed::Random)
+    }
+
+    /// Derive the seed of a single test from the suite's base seed and the test's fully
+    /// qualified name (see 'Prng::label_seed').
+    pub fn test_seed(base_seed: u32, module: &str, name: &str) -> u32 {
+        Prng::label_seed(&format!("{module}.{name}"), base_seed)
+    }
+}
+
+/// ----- Suite -----------------------------------------------------------------
+///
+/// Run a whole suite of tests in parallel. When a timeout is given, no new test is launched once
+/// it has elapsed; tests that didn't get to run are reported as skipped instead (see
+/// 'TestResult::Skipped'). Tests already running are not interrupted.
+pub fn run_suite(
+    tests: Vec<Test>,
+    seed: u32,
+    max_success: usize,
+    timeout: Option<Duration>,
+) -> Vec<TestResult<(Constant, Rc<Type>), PlutusData>> {
+    use rayon::prelude::*;
+
+    let start = Instant::now();
+
+    tests
+        .into_iter()
+        .map(Test::detach)
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|Detached(test)| Detached(test.run_in_suite(start, seed, max_success, timeout)))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|Detached(result)| result)
+        .collect()
+}
+
+/// Like 'run_suite', but yield each result as soon as its test completes (or is skipped),
+/// instead of waiting for the whole suite. Tests still run in parallel, in the background, so
+/// results come in order of completion rather than in the order tests were given. Dropping the
+/// iterator early doesn't stop tests already scheduled.
+pub fn stream_suite(
+    tests: Vec<Test>,
+    seed: u32,
+    max_success: usize,
+    timeout: Option<Duration>,
+) -> impl Iterator<Item = TestResult<(Constant, Rc<Type>), PlutusData>> {
+    use rayon::prelude::*;
+
+    let start = Instant::now();
+
+    let tests = tests.into_iter().map(Test::detach).collect::<Vec<_>>();
+
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        tests
+            .into_par_iter()
+            .for_each_with(sender, |sender, Detached(test)| {
+                // The receiver may be gone already, in which case nobody cares about outcomes.
+                let _ = sender.send(Detached(test.run_in_suite(
+                    start,
+                    seed,
+                    max_success,
+                    timeout,
+                )));
+            })
+    });
+
+    receiver.into_iter().map(|Detached(result)| result)
+}
+
+/// Shuffle tests (or anything really) in an order derived from the given seed. Tests are meant to
+/// be independent from one another, which running them in various orders helps confirm.
+pub fn shuffle<T>(items: &mut [T], seed: u32) {
+    let mut choices = HashedChoices::new(Prng::label_seed("shuffle", seed));
+
+    // Fisher-Yates, with indices drawn from 4 choices at a time. Draws beyond the largest multiple
+    // of the bound are rejected, such that every index is equally likely.
+    for ix in (1..items.len()).rev() {
+        let bound = ix as u64 + 1;
+        let limit = (1 << 32) / bound * bound;
+
+        let draw = loop {
+            let draw = u32::from_be_bytes([0; 4].map(|_| choices.next().unwrap_or_default()));
+            if u64::from(draw) < limit {
+                break u64::from(draw);
+            }
+        };
+
+        items.swap(ix, (draw % bound) as usize);
+    }
+}
+
+/// Enumerate the tests a suite would run, without evaluating anything. This is cheap, and meant
+/// to preview large runs.
+pub fn dry_run(tests: &[Test], max_success: usize) -> Vec<PlannedTest> {
+    tests.iter().map(|test| test.plan(max_success)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedTest {
+    pub module: String,
+    pub name: String,
+    pub is_property: bool,
+    /// Maximum number of times the test runs.
+    pub iterations: usize,
+}
+
+/// A test that didn't run, and why; see 'TestResult::Skipped'.
+#[derive(Debug, Clone)]
+pub struct SkippedTest {
+    pub module: String,
+    pub name: String,
+    pub reason: String,
+}
+
+/// ----- Thread safety -----------------------------------------------------------------
+///
+/// Tests and their results are full of 'Rc's (in programs, types and constants), which aren't
+/// 'Send'. Yet they must cross threads, since suites run in parallel. Sending an 'Rc' is only
+/// unsound when the receiving thread may update a reference count that another thread updates
+/// too; that is, when the value sent shares some node with a value living elsewhere. Tests fresh
+/// out of the code generator do: some terms are cached by the generator and spliced into every
+/// program that needs them, and types are shared with the typed AST.
+///
+/// Tests are therefore detached before being handed to workers (see 'Test::detach'): the copy
+/// owns every node it refers to, none of which is reachable from elsewhere. Workers then only
+/// allocate new nodes, which they either drop or move into the result they send back. Both cross
+/// threads wrapped in 'Detached', which is only ever built by 'Test::detach' and around the results
+/// of running detached tests; hence its 'unsafe impl Send'. Tests must never be sent as is.
+struct Detached<T>(T);
+
+// SAFETY: see 'Thread safety'.
+unsafe impl<T> Send for Detached<T> {}
+
+/// Deep copy of a type, see 'Test::detach'.
+fn detach_type(tipo: &Type) -> Rc<Type> {
+    let alias = |alias: &Option<Rc<TypeAliasAnnotation>>| {
+        alias.as_ref().map(|alias| Rc::new(alias.as_ref().clone()))
+    };
+
+    let all = |types: &[Rc<Type>]| types.iter().map(|t| detach_type(t)).collect();
+
+    Rc::new(match tipo {
+        Type::App {
+            public,
+            contains_opaque,
+            module,
+            name,
+            args,
+            alias: a,
+        } => Type::App {
+            public: *public,
+            contains_opaque: *contains_opaque,
+            module: module.clone(),
+            name: name.clone(),
+            args: all(args),
+            alias: alias(a),
+        },
+        Type::Fn {
+            args,
+            ret,
+            alias: a,
+        } => Type::Fn {
+            args: all(args),
+            ret: detach_type(ret),
+            alias: alias(a),
+        },
+        Type::Var { tipo, alias: a } => Type::Var {
+            tipo: Rc::new(RefCell::new(match &*RefCell::borrow(tipo) {
+                TypeVar::Link { tipo } => TypeVar::Link {
+                    tipo: detach_type(tipo),
+                },
+                var => var.clone(),
+            })),
+            alias: alias(a),
+        },
+        Type::Tuple { elems, alias: a } => Type::Tuple {
+            elems: all(elems),
+            alias: alias(a),
+        },
+    })
+}
+
+/// Deep copy of a constant, see 'Test::detach'.
+fn detach_constant(cst: &Constant) -> Constant {
+    fn detach_uplc_type(tipo: &uplc::ast::Type) -> uplc::ast::Type {
+        match tipo {
+            uplc::ast::Type::List(elem) => uplc::ast::Type::List(detach_uplc_type(elem).into()),
+            uplc::ast::Type::Pair(fst, snd) => {
+                uplc::ast::Type::Pair(detach_uplc_type(fst).into(), detach_uplc_type(snd).into())
+            }
+            tipo => tipo.clone(),
+        }
+    }
+
+    match cst {
+        Constant::ProtoList(tipo, elems) => Constant::ProtoList(
+            detach_uplc_type(tipo),
+            elems.iter().map(detach_constant).collect(),
+        ),
+        Constant::ProtoPair(fst_type, snd_type, fst, snd) => Constant::ProtoPair(
+            detach_uplc_type(fst_type),
+            detach_uplc_type(snd_type),
+            detach_constant(fst).into(),
+            detach_constant(snd).into(),
+        ),
+        cst => cst.clone(),
+    }
 }
 
 /// ----- UnitTest -----------------------------------------------------------------
@@ -165,28 +698,300 @@ pub struct UnitTest {
     pub can_error: bool,
     pub program: Program<Name>,
     pub assertion: Option<Assertion<(Constant, Rc<Type>)>>,
+    /// What a test expected to fail must mention, in its traces or in the error it raises, to
+    /// count as failing for the right reason (see 'TypedTest::expected_error').
+    pub expected_error: Option<String>,
+    /// The (pretty-printed) term the test is expected to evaluate to, if any (see
+    /// 'TypedTest::golden').
+    pub golden: Option<String>,
+    /// The exact traces the test is expected to emit, in order, if any (see
+    /// 'TypedTest::expected_logs').
+    pub expected_logs: Option<Vec<String>>,
+    /// Whether the test body is a mere constant 'True' with no assertion to speak of; such a test
+    /// can't possibly fail (see 'check_strict').
+    pub trivial: bool,
+    pub tags: Vec<String>,
 }
 
-unsafe impl Send for UnitTest {}
-
 impl UnitTest {
+    /// Number of terms in the test's program; see 'term_size'.
+    pub fn program_size(&self) -> usize {
+        term_size(&self.program.term)
+    }
+
     pub fn run<T>(self) -> TestResult<(Constant, Rc<Type>), T> {
-        let mut eval_result = Program::<NamedDeBruijn>::try_from(self.program.clone())
+        self.run_with_cost_model(CostModel::default())
+    }
+
+    /// Evaluate the test, as 'run' does, but without passing any verdict: the evaluation result
+    /// (term, logs and budget) is returned as is, for callers to make their own.
+    pub fn run_raw(&self) -> EvalResult {
+        self.eval(CostModel::default())
+    }
+
+    fn eval(&self, cost_model: CostModel) -> EvalResult {
+        Program::<NamedDeBruijn>::try_from(self.program.clone())
             .unwrap()
-            .eval(ExBudget::max());
+            .eval_with_cost_model(cost_model, ExBudget::max())
+    }
+
+    /// Run the test under each of the given cost models, in order; e.g. to compare what a
+    /// validator costs across protocol versions. See 'run_with_cost_model'.
+    pub fn run_with_cost_models<T>(
+        self,
+        cost_models: Vec<CostModel>,
+    ) -> Vec<TestResult<(Constant, Rc<Type>), T>> {
+        cost_models
+            .into_iter()
+            .map(|cost_model| self.clone().run_with_cost_model(cost_model))
+            .collect()
+    }
+
+    /// Same as 'run', but under the given cost model instead of the default one. Only the spent
+    /// budget is meant to differ from one model to another.
+    pub fn run_with_cost_model<T>(
+        self,
+        cost_model: CostModel,
+    ) -> TestResult<(Constant, Rc<Type>), T> {
+        let start = Instant::now();
+
+        let mut eval_result = self.eval(cost_model);
+
+        let duration = start.elapsed();
+
+        // Tests are meant to return booleans; anything else is a mistake we'd rather report as
+        // such than as a plain failure (or worse, a success).
+        let non_boolean = match eval_result.result() {
+            Ok(Term::Constant(con)) if matches!(con.as_ref(), Constant::Bool(..)) => None,
+            Ok(..) if approximation(&eval_result).is_some() => None,
+            Ok(..) if conjunction(&eval_result).is_some() => None,
+            Ok(Term::Error) | Err(..) => None,
+            Ok(term) => Some(term.to_pretty()),
+        };
+
+        let error = eval_result.result().err().map(|error| error.to_string());
+
+        let error_kind = eval_result.error_kind();
+
+        let traces = eval_result.logs();
+
+        // A test expected to fail must fail for the expected reason, if any.
+        let unexpected_failure = self.can_error
+            && self.expected_error.as_ref().map_or(false, |expected| {
+                !traces
+                    .iter()
+                    .chain(error.iter())
+                    .any(|message| message.contains(expected.as_str()))
+            });
+
+        // Tests that didn't error must also evaluate to their golden term, if any.
+        let golden_mismatch = match (&self.golden, eval_result.result()) {
+            (Some(expected), Ok(term)) if term.to_pretty() != *expected => Some(GoldenMismatch {
+                expected: expected.to_string(),
+                actual: term.to_pretty(),
+            }),
+            _ => None,
+        };
+
+        // Traces are compared as a whole, whether or not the test errored.
+        let logs_mismatch = match &self.expected_logs {
+            Some(expected) if *expected != traces => Some(LogsMismatch {
+                expected: expected.clone(),
+                actual: traces.clone(),
+            }),
+            _ => None,
+        };
 
-        let success = !eval_result.failed(self.can_error);
+        let success = non_boolean.is_none()
+            && !has_failed(&eval_result, self.can_error)
+            && !unexpected_failure
+            && golden_mismatch.is_none()
+            && logs_mismatch.is_none();
+
+        // Tests expected to fail may otherwise fail however they like; others are told apart
+        // whether they returned 'False' or errored.
+        let runtime_error = if self.can_error && !unexpected_failure {
+            None
+        } else {
+            error
+        };
 
         TestResult::UnitTestResult(UnitTestResult {
             success,
             test: self.to_owned(),
             spent_budget: eval_result.cost(),
-            traces: eval_result.logs(),
+            traces,
+            output: eval_result.result().ok(),
             assertion: self.assertion,
+            diff: Vec::new(),
+            non_boolean,
+            runtime_error,
+            error_kind,
+            golden_mismatch,
+            logs_mismatch,
+            duration,
         })
     }
 }
 
+/// A test whose output differs from its golden term (see 'UnitTest::golden').
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenMismatch {
+    pub expected: String,
+    pub actual: String,
+}
+
+/// A test whose traces differ from those it declared (see 'UnitTest::expected_logs').
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogsMismatch {
+    pub expected: Vec<String>,
+    pub actual: Vec<String>,
+}
+
+/// A line of the difference between expected and actual traces; see 'LogsMismatch::diff'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogsDiff<'a> {
+    Same(&'a str),
+    Missing(&'a str),
+    Unexpected(&'a str),
+}
+
+impl LogsMismatch {
+    /// Line-by-line difference between expected and actual traces, keeping as many traces in
+    /// common as possible (i.e. following a longest common subsequence).
+    pub fn diff(&self) -> Vec<LogsDiff<'_>> {
+        let (expected, actual) = (&self.expected, &self.actual);
+
+        // common[i][j] is the length of the longest common subsequence of expected[i..] and
+        // actual[j..].
+        let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+        for i in (0..expected.len()).rev() {
+            for j in (0..actual.len()).rev() {
+                common[i][j] = if expected[i] == actual[j] {
+                    common[i + 1][j + 1] + 1
+                } else {
+                    common[i + 1][j].max(common[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < expected.len() || j < actual.len() {
+            if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+                diff.push(LogsDiff::Same(&expected[i]));
+                i += 1;
+                j += 1;
+            } else if j == actual.len()
+                || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+            {
+                diff.push(LogsDiff::Missing(&expected[i]));
+                i += 1;
+            } else {
+                diff.push(LogsDiff::Unexpected(&actual[j]));
+                j += 1;
+            }
+        }
+
+        diff
+    }
+}
+
+/// Tests may return an 'Approx { actual, expected, tolerance }' instead of a 'Bool', for
+/// comparisons that needn't be exact. It holds when 'actual' lies within 'tolerance' of
+/// 'expected'. Results that aren't approximations yield 'None'.
+fn approximation(result: &EvalResult) -> Option<bool> {
+    let con = match result.result() {
+        Ok(Term::Constant(con)) => con,
+        _ => return None,
+    };
+
+    match con.as_ref() {
+        Constant::Data(PlutusData::Constr(Constr {
+            tag: 121, fields, ..
+        })) => match &fields[..] {
+            [PlutusData::BigInt(actual), PlutusData::BigInt(expected), PlutusData::BigInt(tolerance)] =>
+            {
+                let delta = uplc::machine::value::from_pallas_bigint(actual)
+                    - uplc::machine::value::from_pallas_bigint(expected);
+                let tolerance = uplc::machine::value::from_pallas_bigint(tolerance);
+                Some(-tolerance.clone() <= delta && delta <= tolerance)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Tests may also return a labelled conjunction, 'List<(ByteArray, Bool)>', to check several
+/// things at once. It holds when all its checks do; otherwise, this yields the label of the first
+/// check that doesn't, for reports to point at. Results that aren't conjunctions yield 'None'.
+fn conjunction(result: &EvalResult) -> Option<Result<(), String>> {
+    let con = match result.result() {
+        Ok(Term::Constant(con)) => con,
+        _ => return None,
+    };
+
+    let checks = match con.as_ref() {
+        Constant::ProtoList(_, checks) => checks
+            .iter()
+            .map(|check| match check {
+                Constant::ProtoPair(_, _, label, holds) => match (label.as_ref(), holds.as_ref()) {
+                    (
+                        Constant::Data(PlutusData::BoundedBytes(label)),
+                        Constant::Data(PlutusData::Constr(Constr { tag, .. })),
+                    ) => Some((String::from_utf8_lossy(label).to_string(), *tag == 122)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    };
+
+    match checks.into_iter().find(|(_, holds)| !holds) {
+        Some((label, _)) => Some(Err(label)),
+        None => Some(Ok(())),
+    }
+}
+
+/// Number of terms (i.e. AST nodes) in a UPLC term, which gives a sense of how large a compiled
+/// test is without having to run it. Variables, constants and builtins count as one term each.
+fn term_size<T>(term: &Term<T>) -> usize {
+    let mut size = 0;
+    let mut stack = vec![term];
+
+    // Programs can be quite deep, so we walk them with an explicit stack rather than recursively.
+    while let Some(term) = stack.pop() {
+        size += 1;
+        match term {
+            Term::Var(..) | Term::Constant(..) | Term::Error | Term::Builtin(..) => {}
+            Term::Delay(term) | Term::Force(term) => stack.push(term),
+            Term::Lambda { body, .. } => stack.push(body),
+            Term::Apply { function, argument } => {
+                stack.push(function);
+                stack.push(argument);
+            }
+            Term::Constr { fields, .. } => stack.extend(fields),
+            Term::Case { constr, branches } => {
+                stack.push(constr);
+                stack.extend(branches);
+            }
+        }
+    }
+
+    size
+}
+
+/// Like 'EvalResult::failed', but also judges approximations and conjunctions; see
+/// 'approximation' and 'conjunction'.
+fn has_failed(result: &EvalResult, can_error: bool) -> bool {
+    match approximation(result).or_else(|| conjunction(result).map(|checks| checks.is_ok())) {
+        Some(holds) => holds == can_error,
+        None => result.failed(can_error),
+    }
+}
+
 /// ----- PropertyTest -----------------------------------------------------------------
 ///
 #[derive(Debug, Clone)]
@@ -197,9 +1002,41 @@ pub struct PropertyTest {
     pub can_error: bool,
     pub program: Program<Name>,
     pub fuzzer: Fuzzer<Name>,
+    pub tags: Vec<String>,
+    /// Choices toward which counterexamples are shrunk, instead of zero; see
+    /// 'TypedTest::shrink_target'. Choices beyond the target are shrunk toward zero.
+    pub shrink_target: Vec<u8>,
+    compiled: Rc<Compiled>,
+}
+
+/// The property and its fuzzer, converted once to the form in which they're evaluated. The
+/// conversion doesn't depend on the values being tested, so there's no point in redoing it for
+/// each of the (many) evaluations of a run; values are simply applied to the converted programs.
+#[derive(Debug, Clone)]
+struct Compiled {
+    property: Program<NamedDeBruijn>,
+    /// The property with all its traces erased; see 'PropertyTest::eval_untraced'.
+    untraced: Program<NamedDeBruijn>,
+    fuzzer: Program<NamedDeBruijn>,
 }
 
-unsafe impl Send for PropertyTest {}
+impl Compiled {
+    fn new(property: &Program<Name>, fuzzer: &Program<Name>) -> Self {
+        let convert = |program: &Program<Name>| {
+            Program::<NamedDeBruijn>::try_from(program.clone())
+                .expect("failed to convert program to NamedDeBruijn")
+        };
+
+        Compiled {
+            property: convert(property),
+            untraced: convert(&Program {
+                version: property.version,
+                term: erase_traces(&property.term),
+            }),
+            fuzzer: convert(fuzzer),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Fuzzer<T> {
@@ -213,1295 +1050,7611 @@ pub struct Fuzzer<T> {
     pub stripped_type_info: Rc<Type>,
 }
 
-#[derive(Debug, Clone, thiserror::Error, miette::Diagnostic)]
-#[error("Fuzzer exited unexpectedly: {uplc_error}")]
-pub struct FuzzerError {
-    traces: Vec<String>,
-    uplc_error: uplc::machine::Error,
+impl Fuzzer<Name> {
+    /// Sample a single value from a seed, reified as an Aiken expression of the fuzzer's type.
+    /// This is meant for tooling after example values (e.g. documentation), outside of any
+    /// property. Also returns a seed to generate the next value from, such that values can be
+    /// generated in sequence; or 'None' should the fuzzer not yield any value (or fail).
+    pub fn generate(
+        &self,
+        seed: u32,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+    ) -> Option<(u32, UntypedExpr)> {
+        let program = Program::<NamedDeBruijn>::try_from(self.program.clone())
+            .expect("failed to convert program to NamedDeBruijn");
+
+        let (next_prng, value) = Prng::from_seed(seed, 0).sample(&program).ok()??;
+
+        let next_seed = next_prng
+            .seed()
+            .and_then(|seed| seed.get(..4))
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .expect("a seeded Prng yields a seeded Prng");
+
+        let value = UntypedExpr::reify_data(data_types, value.clone(), &self.type_info)
+            .unwrap_or_else(|_| UntypedExpr::reify_blind(value));
+
+        Some((next_seed, value))
+    }
 }
 
-impl PropertyTest {
-    pub const DEFAULT_MAX_SUCCESS: usize = 100;
+/// Options of a property test run, see 'PropertyTest::run_with'. Options start from a seed and
+/// default values, and are then refined one at a time:
+///
+///   PropertyTestOptions::new(seed)
+///       .max_success(1000)
+///       .shrink_timeout(Duration::from_secs(5))
+///
+pub struct PropertyTestOptions<'a> {
+    seed: u32,
+    max_success: usize,
+    max_counterexamples: usize,
+    record_trail: bool,
+    check_determinism: bool,
+    shrink_timeout: Option<Duration>,
+    on_shrink: Option<&'a mut dyn FnMut(ShrinkProgress)>,
+    corpus: Vec<Vec<u8>>,
+    max_choices: usize,
+    stop_condition: StopCondition,
+    shrink_schedule: ShrinkSchedule,
+}
 
-    /// Run a property test from a given seed. The property is run at most DEFAULT_MAX_SUCCESS times. It
-    /// may stops earlier on failure; in which case a 'counterexample' is returned.
-    pub fn run<U>(self, seed: u32, n: usize) -> TestResult<U, PlutusData> {
-        let mut labels = BTreeMap::new();
-        let mut remaining = n;
+impl<'a> PropertyTestOptions<'a> {
+    pub fn new(seed: u32) -> Self {
+        PropertyTestOptions {
+            seed,
+            max_success: PropertyTest::DEFAULT_MAX_SUCCESS,
+            max_counterexamples: 1,
+            record_trail: false,
+            check_determinism: false,
+            shrink_timeout: None,
+            on_shrink: None,
+            corpus: Vec::new(),
+            max_choices: PropertyTest::DEFAULT_MAX_CHOICES,
+            stop_condition: StopCondition::FirstFailure,
+            shrink_schedule: ShrinkSchedule::default(),
+        }
+    }
 
-        let (traces, counterexample, iterations) =
-            match self.run_n_times(&mut remaining, Prng::from_seed(seed), &mut labels) {
-                Ok(None) => (Vec::new(), Ok(None), n),
-                Ok(Some(counterexample)) => (
-                    self.eval(&counterexample.value)
-                        .logs()
-                        .into_iter()
-                        .filter(|s| PropertyTest::extract_label(s).is_none())
-                        .collect(),
-                    Ok(Some(counterexample.value)),
-                    n - remaining + 1,
-                ),
-                Err(FuzzerError { traces, uplc_error }) => (
-                    traces
-                        .into_iter()
-                        .filter(|s| PropertyTest::extract_label(s).is_none())
-                        .collect(),
-                    Err(uplc_error),
-                    0,
-                ),
-            };
+    /// Maximum number of iterations to run.
+    pub fn max_success(mut self, max_success: usize) -> Self {
+        self.max_success = max_success;
+        self
+    }
 
-        TestResult::PropertyTestResult(PropertyTestResult {
-            test: self,
-            counterexample,
-            iterations,
-            labels,
-            traces,
-        })
+    /// When greater than one, the property keeps running after the first failure, until it has
+    /// found as many distinct (shrunk) counterexamples or has run all its iterations. This is
+    /// useful when a property fails for several unrelated reasons.
+    pub fn max_counterexamples(mut self, max_counterexamples: usize) -> Self {
+        self.max_counterexamples = max_counterexamples;
+        self
     }
 
-    fn run_n_times<'a>(
-        &'a self,
-        remaining: &mut usize,
-        initial_prng: Prng,
-        labels: &mut BTreeMap<String, usize>,
-    ) -> Result<Option<Counterexample<'a>>, FuzzerError> {
-        let mut prng = initial_prng;
-        let mut counterexample = None;
+    /// Record the choices drawn at every iteration in the result. This is purely diagnostic, to
+    /// audit how a fuzzer varies its output.
+    pub fn record_trail(mut self, record_trail: bool) -> Self {
+        self.record_trail = record_trail;
+        self
+    }
 
-        while *remaining > 0 && counterexample.is_none() {
-            (prng, counterexample) = self.run_once(prng, labels)?;
-            *remaining -= 1;
-        }
+    /// Replay the choices of the counterexample through the fuzzer before trusting the outcome.
+    /// Shrinking assumes fuzzers regenerate the same value from the same choices; when they
+    /// don't, the result is flagged as such instead of reporting a confusing counterexample.
+    pub fn check_determinism(mut self, check_determinism: bool) -> Self {
+        self.check_determinism = check_determinism;
+        self
+    }
 
-        Ok(counterexample)
+    /// Bound the time spent simplifying each counterexample; the best counterexample found so far
+    /// is reported once it has elapsed.
+    pub fn shrink_timeout(mut self, shrink_timeout: Duration) -> Self {
+        self.shrink_timeout = Some(shrink_timeout);
+        self
     }
 
-    fn run_once(
-        &self,
-        prng: Prng,
-        labels: &mut BTreeMap<String, usize>,
-    ) -> Result<(Prng, Option<Counterexample<'_>>), FuzzerError> {
-        let (next_prng, value) = prng
-            .sample(&self.fuzzer.program)?
-            .expect("A seeded PRNG returned 'None' which indicates a fuzzer is ill-formed and implemented wrongly; please contact library's authors.");
+    /// Called each time the counterexample being simplified improves, which lets interactive
+    /// tools show progress on long shrinks.
+    pub fn on_shrink(mut self, on_shrink: &'a mut dyn FnMut(ShrinkProgress)) -> Self {
+        self.on_shrink = Some(on_shrink);
+        self
+    }
 
-        let mut result = self.eval(&value);
+    /// Known-interesting inputs, as sequences of choices, replayed in order before any random
+    /// sampling. This guarantees that boundary cases are tried on every run. They come on top of
+    /// 'max_success' iterations; and the first one to fail ends the run.
+    pub fn corpus(mut self, corpus: Vec<Vec<u8>>) -> Self {
+        self.corpus = corpus;
+        self
+    }
 
-        for s in result.logs() {
-            // NOTE: There may be other log outputs that interefere with labels. So *by
-            // convention*, we treat as label strings that starts with a NUL byte, which
-            // should be a guard sufficient to prevent inadvertent clashes.
-            if let Some(label) = PropertyTest::extract_label(&s) {
-                labels
-                    .entry(label)
-                    .and_modify(|count| *count += 1)
-                    .or_insert(1);
-            }
+    /// Maximum number of choices a single input may be generated from. Inputs beyond that are
+    /// almost always the sign of a runaway recursive fuzzer, and would take ages to shrink; the
+    /// run stops as soon as one shows up, and is reported as an error.
+    pub fn max_choices(mut self, max_choices: usize) -> Self {
+        self.max_choices = max_choices;
+        self
+    }
+
+    /// Whether the run stops once its counterexample(s) are found, or goes on over all its
+    /// iterations; see 'StopCondition'.
+    pub fn stop_condition(mut self, stop_condition: StopCondition) -> Self {
+        self.stop_condition = stop_condition;
+        self
+    }
+
+    /// Chunk sizes tried by the shrinker's chunked passes; see 'ShrinkSchedule'.
+    pub fn shrink_schedule(mut self, shrink_schedule: ShrinkSchedule) -> Self {
+        self.shrink_schedule = shrink_schedule;
+        self
+    }
+}
+
+/// Chunk sizes tried, in order, by the chunked passes of the shrinker (see
+/// 'Counterexample::simplify'). Large chunks make large inputs collapse faster, whereas tiny
+/// inputs gain nothing from them. Chunks of size zero are ignored.
+///
+///   ShrinkSchedule::default()
+///       .deletion(vec![32, 16, 8, 4, 2, 1])
+///       .zeroing(vec![2])
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShrinkSchedule {
+    deletion: Option<Vec<usize>>,
+    zeroing: Vec<usize>,
+}
+
+impl Default for ShrinkSchedule {
+    /// Deletions start from chunks sized after the length of the choices (see
+    /// 'Counterexample::max_deletion_chunk') and halve down to 1; zeroing goes through chunks of
+    /// 8, 4 and 2, since single choices are handled by the binary search that follows.
+    fn default() -> Self {
+        ShrinkSchedule {
+            deletion: None,
+            zeroing: vec![8, 4, 2],
         }
+    }
+}
 
-        // NOTE: We do NOT pass self.can_error here, because when searching for
-        // failing properties, we do want to _keep running_ until we find a
-        // a failing case. It may not occur on the first run.
-        if result.failed(false) {
-            let mut counterexample = Counterexample {
-                value,
-                choices: next_prng.choices(),
-                cache: Cache::new(|choices| {
-                    match Prng::from_choices(choices).sample(&self.fuzzer.program) {
-                        Err(..) => Status::Invalid,
-                        Ok(None) => Status::Invalid,
-                        Ok(Some((_, value))) => {
-                            let result = self.eval(&value);
-
-                            let is_failure = result.failed(self.can_error);
-
-                            let expect_failure = self.can_error;
-
-                            // If the test no longer fails, it isn't better as we're only
-                            // interested in counterexamples.
-                            if (expect_failure && is_failure) || (!expect_failure && !is_failure) {
-                                Status::Ignore
-                            } else {
-                                Status::Keep(value)
-                            }
-                        }
-                    }
-                }),
-            };
+impl ShrinkSchedule {
+    /// Chunk sizes of the deletion pass, replacing the default ones derived from the length of
+    /// the choices.
+    pub fn deletion(mut self, chunks: Vec<usize>) -> Self {
+        self.deletion = Some(chunks.into_iter().filter(|k| *k > 0).collect());
+        self
+    }
 
-            if !counterexample.choices.is_empty() {
-                counterexample.simplify();
-            }
+    /// Chunk sizes of the zeroing pass.
+    pub fn zeroing(mut self, chunks: Vec<usize>) -> Self {
+        self.zeroing = chunks.into_iter().filter(|k| *k > 0).collect();
+        self
+    }
 
-            Ok((next_prng, Some(counterexample)))
-        } else {
-            Ok((next_prng, None))
+    /// Chunk sizes of the deletion pass, for choices of the given length.
+    fn deletion_chunks(&self, len: usize) -> Vec<usize> {
+        match &self.deletion {
+            Some(chunks) => chunks.clone(),
+            None => {
+                let max = Counterexample::max_deletion_chunk(len);
+                std::iter::successors(Some(max), |k| Some(k / 2))
+                    .take_while(|k| *k > 0)
+                    .collect()
+            }
         }
     }
+}
 
-    pub fn eval(&self, value: &PlutusData) -> EvalResult {
-        let program = self.program.apply_data(value.clone());
+/// When a property run ends, once it has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopCondition {
+    /// Stop as soon as the counterexample(s) are found.
+    FirstFailure,
+    /// Keep going over all iterations once the counterexample(s) are found, for statistics (e.g.
+    /// labels) to cover the whole run. Later failures are neither shrunk nor reported.
+    RunAll,
+}
 
-        Program::<NamedDeBruijn>::try_from(program)
-            .unwrap()
-            .eval(ExBudget::max())
+/// Bounds on the work done while running a property; see 'PropertyTestOptions'.
+#[derive(Debug, Clone)]
+struct Limits {
+    shrink_timeout: Option<Duration>,
+    max_choices: usize,
+    shrink_schedule: ShrinkSchedule,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            shrink_timeout: None,
+            max_choices: PropertyTest::DEFAULT_MAX_CHOICES,
+            shrink_schedule: ShrinkSchedule::default(),
+        }
     }
+}
 
-    fn extract_label(s: &str) -> Option<String> {
-        if s.starts_with('\0') {
-            Some(s.split_at(1).1.to_string())
+impl Limits {
+    fn check(&self, choices: usize) -> Result<(), FuzzerError> {
+        if choices > self.max_choices {
+            Err(FuzzerError::Oversized { choices })
         } else {
-            None
+            Ok(())
         }
     }
 }
 
-/// ----- PRNG -----------------------------------------------------------------
-///
-/// A Pseudo-random generator (PRNG) used to produce random values for fuzzers.
-/// Note that the randomness isn't actually managed by the Rust framework, it
-/// entirely relies on properties of hashing algorithm on-chain (e.g. blake2b).
-///
-/// The PRNG can have two forms:
-///
-/// 1. Seeded: which occurs during the initial run of a property. Each time a
-///    number is drawn from the PRNG, a new seed is created. We retain all the
-///    choices drawn in a _choices_ vector.
-///
-/// 2. Replayed: which is used to replay a Prng sequenced from a list of known
-///    choices. This happens when shrinking an example. Instead of trying to
-///    shrink the value directly, we shrink the PRNG sequence with the hope that
-///    it will generate a smaller value. This implies that generators tend to
-///    generate smaller values when drawing smaller numbers.
-///
-#[derive(Debug)]
-pub enum Prng {
-    Seeded { choices: Vec<u8>, uplc: PlutusData },
-    Replayed { choices: Vec<u8>, uplc: PlutusData },
+#[derive(Debug, Clone, thiserror::Error, miette::Diagnostic)]
+pub enum FuzzerError {
+    #[error("Fuzzer exited unexpectedly: {uplc_error}")]
+    Exited {
+        traces: Vec<String>,
+        uplc_error: uplc::machine::Error,
+    },
+    #[error("Fuzzer generated an input from {choices} choices, more than allowed")]
+    Oversized { choices: usize },
 }
 
-impl Prng {
-    /// Constructor tag for Prng's 'Seeded'
-    const SEEDED: u64 = 0;
-    /// Constructor tag for Prng's 'Replayed'
-    const REPLAYED: u64 = 1;
+impl PropertyTest {
+    pub const DEFAULT_MAX_SUCCESS: usize = 100;
 
-    /// Constructor tag for Option's 'Some'
-    const SOME: u64 = 0;
-    /// Constructor tag for Option's 'None'
-    const NONE: u64 = 1;
+    /// See 'PropertyTestOptions::max_choices'.
+    pub const DEFAULT_MAX_CHOICES: usize = 100_000;
 
-    pub fn uplc(&self) -> PlutusData {
-        match self {
-            Prng::Seeded { uplc, .. } => uplc.clone(),
-            Prng::Replayed { uplc, .. } => uplc.clone(),
-        }
+    /// Number of terms in the property's program, not counting its fuzzer; see 'term_size'.
+    pub fn program_size(&self) -> usize {
+        term_size(&self.program.term)
     }
 
-    pub fn choices(&self) -> Vec<u8> {
-        match self {
-            Prng::Seeded { choices, .. } => {
-                let mut choices = choices.to_vec();
-                choices.reverse();
-                choices
-            }
-            Prng::Replayed { choices, .. } => choices.to_vec(),
-        }
+    /// Number of iterations sampled sequentially from a same seeded stream. Streams are sampled
+    /// in parallel.
+    const SAMPLING_CHUNK_SIZE: usize = 25;
+
+    /// Run a property test from a given seed. The property is run at most `n` times. It may stops
+    /// earlier on failure; in which case a 'counterexample' is returned. See 'run_with' to tune
+    /// the run further.
+    pub fn run<U>(self, seed: u32, n: usize) -> TestResult<U, PlutusData> {
+        self.run_with(PropertyTestOptions::new(seed).max_success(n))
     }
 
-    /// Construct a Pseudo-random number generator from a seed.
-    pub fn from_seed(seed: u32) -> Prng {
-        let mut digest = [0u8; 32];
-        let mut context = Blake2b::new(32);
-        context.input(&seed.to_be_bytes()[..]);
-        context.result(&mut digest);
+    /// Run a property test with the given options. See 'PropertyTestOptions' for what each
+    /// option does.
+    pub fn run_with<U>(self, options: PropertyTestOptions<'_>) -> TestResult<U, PlutusData> {
+        let PropertyTestOptions {
+            seed,
+            max_success: n,
+            max_counterexamples,
+            record_trail,
+            check_determinism,
+            shrink_timeout,
+            on_shrink,
+            corpus,
+            max_choices,
+            stop_condition,
+            shrink_schedule,
+        } = options;
+
+        let limits = &Limits {
+            shrink_timeout,
+            max_choices,
+            shrink_schedule,
+        };
 
-        Prng::Seeded {
-            choices: vec![],
-            uplc: Data::constr(
-                Prng::SEEDED,
-                vec![
-                    Data::bytestring(digest.to_vec()), // Prng's seed
-                    Data::bytestring(vec![]),          // Random choices
-                ],
+        let mut labels = BTreeMap::new();
+        let mut remaining = n;
+        let mut stalled_fuzzer = false;
+
+        let start = Instant::now();
+
+        let progress = match on_shrink {
+            Some(on_shrink) => on_shrink,
+            None => &mut |_| {},
+        };
+
+        let corpus = self.run_corpus(&corpus, &mut labels, limits, progress);
+
+        let replayed = corpus.as_ref().map(|(replayed, _)| *replayed).unwrap_or(0);
+
+        let outcome = match corpus.map(|(_, counterexample)| counterexample) {
+            Err(error) => Err(error),
+            Ok(Some(counterexample)) => Ok((vec![counterexample], Some(replayed))),
+            Ok(None) if max_counterexamples > 1 => self.collect_counterexamples(
+                &mut remaining,
+                seed,
+                &mut labels,
+                max_counterexamples,
+                limits,
+                progress,
             ),
+            Ok(None) => self
+                .run_n_times(
+                    &mut remaining,
+                    seed,
+                    &mut labels,
+                    &mut stalled_fuzzer,
+                    limits,
+                    progress,
+                )
+                .map(|counterexample| {
+                    let failed_at = counterexample.as_ref().map(|_| n - remaining);
+                    (counterexample.into_iter().collect(), failed_at)
+                }),
         }
-    }
+        .map(
+            |(counterexamples, failed_at): (Vec<Counterexample<'_>>, _)| {
+                let choices = counterexamples
+                    .first()
+                    .map(|counterexample| (counterexample.choices.clone(), counterexample.size))
+                    .unwrap_or_default();
+
+                let shrink_stats = counterexamples
+                    .first()
+                    .map(|counterexample| (counterexample.stats, counterexample.invalid_replays))
+                    .unwrap_or_default();
+
+                let nondeterministic = check_determinism
+                    && counterexamples
+                        .first()
+                        .map(|counterexample| !self.is_deterministic(counterexample))
+                        .unwrap_or(false);
+
+                let values = counterexamples
+                    .into_iter()
+                    .map(|counterexample| counterexample.value)
+                    .collect::<Vec<_>>();
+
+                (values, choices, shrink_stats, nondeterministic, failed_at)
+            },
+        );
 
-    /// Construct a Pseudo-random number generator from a pre-defined list of choices.
-    pub fn from_choices(choices: &[u8]) -> Prng {
-        Prng::Replayed {
-            uplc: Data::constr(
-                Prng::REPLAYED,
-                vec![
-                    Data::integer(choices.len().into()),
-                    Data::bytestring(choices.iter().rev().cloned().collect::<Vec<_>>()),
-                ],
-            ),
-            choices: choices.to_vec(),
+        let nondeterministic = matches!(outcome, Ok((_, _, _, true, _)));
+
+        let (shrink_stats, invalid_replays) = match outcome {
+            Ok((_, _, shrink_stats, ..)) => shrink_stats,
+            Err(..) => (ShrinkStats::default(), 0),
+        };
+
+        let oversized_input = match outcome {
+            Err(FuzzerError::Oversized { choices }) => Some(choices),
+            _ => None,
+        };
+
+        let failed_at = match outcome {
+            // Failures found by random sampling come after the whole corpus.
+            Ok((.., Some(failed_at))) if remaining < n => Some(replayed + failed_at),
+            Ok((.., failed_at)) => failed_at,
+            Err(..) => None,
+        };
+
+        if stop_condition == StopCondition::RunAll
+            && matches!(outcome, Ok((ref counterexamples, ..)) if !counterexamples.is_empty())
+        {
+            remaining -= self.survey(seed, n - remaining, remaining, &mut labels, limits);
         }
-    }
 
-    /// Generate a pseudo-random value from a fuzzer using the given PRNG.
-    pub fn sample(
-        &self,
-        fuzzer: &Program<Name>,
-    ) -> Result<Option<(Prng, PlutusData)>, FuzzerError> {
-        let program = Program::<NamedDeBruijn>::try_from(fuzzer.apply_data(self.uplc())).unwrap();
-        let mut result = program.eval(ExBudget::max());
-        result
-            .result()
-            .map_err(|uplc_error| FuzzerError {
-                traces: result.logs(),
-                uplc_error,
-            })
-            .map(Prng::from_result)
+        let (
+            traces,
+            counterexample,
+            counterexamples,
+            (choices, size),
+            iterations,
+            untraced_divergence,
+            eval_error,
+            failed_check,
+        ) = match outcome {
+            Ok((counterexamples, ..)) if counterexamples.is_empty() => (
+                Vec::new(),
+                Ok(None),
+                Vec::new(),
+                (Vec::new(), 0),
+                replayed + n,
+                false,
+                None,
+                None,
+            ),
+            Ok((counterexamples, choices, ..)) => {
+                let counterexample = &counterexamples[0];
+
+                let mut result = self.eval(counterexample);
+
+                let untraced_divergence = has_failed(&result, self.can_error)
+                    != has_failed(&self.eval_untraced(counterexample), self.can_error);
+
+                (
+                    result
+                        .logs()
+                        .into_iter()
+                        .filter(|s| PropertyTest::extract_label(s).is_none())
+                        .collect(),
+                    Ok(Some(counterexample.clone())),
+                    counterexamples,
+                    choices,
+                    replayed + (n - remaining),
+                    untraced_divergence,
+                    result.result().err(),
+                    conjunction(&result).and_then(Result::err),
+                )
+            }
+            Err(FuzzerError::Oversized { .. }) => (
+                Vec::new(),
+                Ok(None),
+                Vec::new(),
+                (Vec::new(), 0),
+                0,
+                false,
+                None,
+                None,
+            ),
+            Err(FuzzerError::Exited { traces, uplc_error }) => (
+                traces
+                    .into_iter()
+                    .filter(|s| PropertyTest::extract_label(s).is_none())
+                    .collect(),
+                Err(uplc_error),
+                Vec::new(),
+                (Vec::new(), 0),
+                0,
+                false,
+                None,
+                None,
+            ),
+        };
+
+        let duration = start.elapsed();
+
+        let trail = if record_trail {
+            self.trail(seed, iterations.saturating_sub(replayed))
+        } else {
+            Vec::new()
+        };
+
+        let (failing_program, scenario) = match &counterexample {
+            Ok(Some(counterexample)) => (
+                Some(self.apply(counterexample)),
+                self.scenario(&choices, size),
+            ),
+            _ => (None, Vec::new()),
+        };
+
+        TestResult::PropertyTestResult(PropertyTestResult {
+            counterexample,
+            counterexamples,
+            choices,
+            size,
+            iterations,
+            failed_at,
+            trail,
+            labels,
+            traces,
+            duration,
+            untraced_divergence,
+            nondeterministic,
+            stalled_fuzzer,
+            eval_error,
+            shrink_stats,
+            oversized_input,
+            failed_check,
+            invalid_replays,
+            failing_program,
+            scenario,
+            ..PropertyTestResult::empty(self, Some(seed))
+        })
     }
 
-    /// Obtain a Prng back from a fuzzer execution. As a reminder, fuzzers have the following
-    /// signature:
-    ///
-    ///   type Fuzzer<a> = fn(Prng) -> Option<(Prng, a)>
-    ///
-    /// In nominal scenarios (i.e. when the fuzzer is made from a seed and evolve pseudo-randomly),
-    /// it cannot yield 'None'. When replayed however, we can't easily guarantee that the changes
-    /// made during shrinking aren't breaking underlying invariants (if only, because we run out of
-    /// values to replay). In such case, the replayed sequence is simply invalid and the fuzzer
-    /// aborted altogether with 'None'.
-    pub fn from_result(result: Term<NamedDeBruijn>) -> Option<(Self, PlutusData)> {
-        /// Interpret the given 'PlutusData' as one of two Prng constructors.
-        fn as_prng(cst: &PlutusData) -> Prng {
-            if let PlutusData::Constr(Constr { tag, fields, .. }) = cst {
-                if *tag == 121 + Prng::SEEDED {
-                    if let [PlutusData::BoundedBytes(bytes), PlutusData::BoundedBytes(choices)] =
-                        &fields[..]
-                    {
-                        return Prng::Seeded {
-                            choices: choices.to_vec(),
-                            uplc: PlutusData::Constr(Constr {
-                                tag: 121 + Prng::SEEDED,
-                                fields: vec![
-                                    PlutusData::BoundedBytes(bytes.to_owned()),
-                                    // Clear choices between seeded runs, to not
-                                    // accumulate ALL choices ever made.
-                                    PlutusData::BoundedBytes(vec![].into()),
-                                ],
-                                any_constructor: None,
-                            }),
-                        };
-                    }
-                }
+    /// Run a property test under a deterministic sequence of seeds, with the default number of
+    /// iterations per seed. The sweep stops at the first seed yielding a counterexample (or a
+    /// fuzzer error), in which case the result carries that seed. Otherwise, iterations and labels
+    /// are aggregated across all seeds, and the result carries none.
+    pub fn run_sweep<U>(&self, seeds: impl Iterator<Item = u32>) -> TestResult<U, PlutusData> {
+        let mut sweep = PropertyTestResult::empty(self.clone(), None);
+
+        for seed in seeds {
+            let result = self
+                .clone()
+                .run::<U>(seed, Self::DEFAULT_MAX_SUCCESS)
+                .into_property();
+
+            if !matches!(result.counterexample, Ok(None)) || result.oversized_input.is_some() {
+                return TestResult::PropertyTestResult(PropertyTestResult {
+                    iterations: sweep.iterations + result.iterations,
+                    duration: sweep.duration + result.duration,
+                    ..result
+                });
+            }
 
-                if *tag == 121 + Prng::REPLAYED {
-                    if let [PlutusData::BigInt(..), PlutusData::BoundedBytes(choices)] = &fields[..]
-                    {
-                        return Prng::Replayed {
-                            choices: choices.to_vec(),
-                            uplc: cst.clone(),
-                        };
-                    }
-                }
+            for (label, count) in result.labels {
+                sweep
+                    .labels
+                    .entry(label)
+                    .and_modify(|total| *total += count)
+                    .or_insert(count);
             }
 
-            unreachable!("malformed Prng: {cst:#?}")
+            sweep.iterations += result.iterations;
+            sweep.duration += result.duration;
         }
 
-        if let Term::Constant(rc) = &result {
-            if let Constant::Data(PlutusData::Constr(Constr { tag, fields, .. })) = &rc.borrow() {
-                if *tag == 121 + Prng::SOME {
-                    if let [PlutusData::Array(elems)] = &fields[..] {
-                        if let [new_seed, value] = &elems[..] {
-                            return Some((as_prng(new_seed), value.clone()));
-                        }
-                    }
-                }
+        TestResult::PropertyTestResult(sweep)
+    }
 
-                // May occurs when replaying a fuzzer from a shrinked sequence of
-                // choices. If we run out of choices, or a choice end up being
-                // invalid as per the expectation, the fuzzer can't go further and
-                // fail.
-                if *tag == 121 + Prng::NONE {
-                    return None;
+    /// Run a property test once for each of `seeds` consecutive seeds starting at `base_seed`,
+    /// with the default number of iterations per seed. Unlike 'run_sweep', this never stops
+    /// early: the point is to measure how rare failures are, not merely to find one.
+    pub fn estimate_failure_rate(&self, seeds: usize, base_seed: u32) -> FailureStats {
+        let mut stats = FailureStats {
+            seeds,
+            failures: 0,
+            errors: 0,
+            iterations: 0,
+            examples: Vec::new(),
+        };
+
+        for ix in 0..seeds {
+            let seed = base_seed.wrapping_add(ix as u32);
+
+            let result = self
+                .clone()
+                .run::<()>(seed, Self::DEFAULT_MAX_SUCCESS)
+                .into_property();
+
+            stats.iterations += result.iterations;
+            match result.counterexample {
+                Ok(None) if result.oversized_input.is_some() => stats.errors += 1,
+                Ok(None) => {}
+                Ok(Some(counterexample)) => {
+                    stats.failures += 1;
+                    if stats.examples.len() < FailureStats::MAX_EXAMPLES
+                        && stats
+                            .examples
+                            .iter()
+                            .all(|(_, known)| *known != counterexample)
+                    {
+                        stats.examples.push((seed, counterexample));
+                    }
                 }
+                Err(..) => stats.errors += 1,
             }
         }
 
-        unreachable!("Fuzzer yielded a malformed result? {result:#?}")
+        stats
     }
-}
 
-/// ----- Counterexample -----------------------------------------------------------------
-///
-/// A counterexample is constructed from a test failure. It holds a value, and a sequence
-/// of random choices that led to this value. It holds a reference to the underlying
-/// property and fuzzer. In many cases, a counterexample can be simplified (a.k.a "shrinked")
-/// into a smaller counterexample.
-pub struct Counterexample<'a> {
-    pub value: PlutusData,
-    pub choices: Vec<u8>,
-    pub cache: Cache<'a, PlutusData>,
-}
+    /// Run the property under each of the given seeds, and compare the choices of the (shrunk)
+    /// counterexample with those recorded for that seed; e.g. by a previous version of the
+    /// framework. Seeds for which the shrinker now ends up further, or no longer finds any
+    /// counterexample, are reported. Ending up with simpler choices isn't a regression.
+    ///
+    /// Choices are compared as by the shrinker: shorter ones are simpler, and so are smaller ones
+    /// amongst those of a same length.
+    pub fn check_shrink_regressions(
+        &self,
+        expected: &BTreeMap<u32, Vec<u8>>,
+        n: usize,
+    ) -> Vec<ShrinkRegression> {
+        let key = |choices: &[u8]| (choices.len(), choices.to_vec());
+
+        expected
+            .iter()
+            .filter_map(|(seed, expected)| {
+                let actual = match self.clone().run::<()>(*seed, n).into_property() {
+                    PropertyTestResult {
+                        counterexample: Ok(Some(..)),
+                        choices,
+                        ..
+                    } => Some(choices),
+                    _ => None,
+                };
 
-impl<'a> Counterexample<'a> {
-    fn consider(&mut self, choices: &[u8]) -> bool {
-        if choices == self.choices {
-            return true;
+                match actual {
+                    Some(ref actual) if key(actual) <= key(expected) => None,
+                    actual => Some(ShrinkRegression {
+                        seed: *seed,
+                        expected: expected.clone(),
+                        actual,
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Reconstruct a failure from a bundle (see 'PropertyTestResult::bundle') and replay it
+    /// against this property. The bundle must have been produced for this very test, and its
+    /// choices must lead the fuzzer to the recorded counterexample.
+    ///
+    /// The replay runs a single iteration; the result holds no counterexample if the property no
+    /// longer fails on the bundled value.
+    pub fn load_bundle<U>(self, bundle: &Bundle) -> Result<TestResult<U, PlutusData>, BundleError> {
+        if bundle.version > Bundle::VERSION || bundle.version == 0 {
+            return Err(BundleError::UnsupportedVersion {
+                version: bundle.version,
+            });
         }
 
-        match self.cache.get(choices) {
-            Status::Invalid | Status::Ignore => false,
-            Status::Keep(value) => {
-                // If these new choices are shorter or smaller, then we pick them
-                // as new choices and inform that it's been an improvement.
-                if choices.len() <= self.choices.len() || choices < &self.choices[..] {
-                    self.value = value;
-                    self.choices = choices.to_vec();
-                    true
-                } else {
-                    false
+        if bundle.module != self.module || bundle.name != self.name {
+            return Err(BundleError::TestMismatch {
+                expected: format!("{}.{}", self.module, self.name),
+                found: format!("{}.{}", bundle.module, bundle.name),
+            });
+        }
+
+        let choices = hex::decode(&bundle.choices)
+            .map_err(|_| BundleError::Malformed { field: "choices" })?;
+
+        let recorded = hex::decode(&bundle.counterexample)
+            .ok()
+            .and_then(|bytes| uplc::plutus_data(&bytes).ok())
+            .ok_or(BundleError::Malformed {
+                field: "counterexample",
+            })?;
+
+        let start = Instant::now();
+
+        let value = match Prng::from_choices(&choices)
+            .with_size(bundle.size)
+            .sample(&self.compiled.fuzzer)
+        {
+            Ok(Some((_, value))) if value == recorded => value,
+            _ => return Err(BundleError::Diverged),
+        };
+
+        Ok(self.run_single(value, choices, bundle.size, bundle.seed, start))
+    }
+
+    /// Run the property exactly once, against the simplest value its fuzzer yields (i.e. drawn
+    /// from choices that are all zeros), without searching nor shrinking. This gives near-instant
+    /// feedback that a property compiles and runs at all, before committing to a full run.
+    ///
+    /// The fuzzer is first given no choices at all, then twice as many zeros each time it runs
+    /// out of them. Fuzzers that yield nothing even from 'DEFAULT_MAX_CHOICES' zeros leave the
+    /// property untried; which doesn't count as a pass (see 'lacks_iterations').
+    pub fn smoke<U>(self) -> TestResult<U, PlutusData> {
+        let start = Instant::now();
+
+        let mut supplied = 0;
+
+        let sample = loop {
+            let prng = Prng::from_source(&mut std::iter::repeat(0), supplied);
+
+            match prng.sample(&self.compiled.fuzzer) {
+                Ok(None) if supplied < Self::DEFAULT_MAX_CHOICES => {
+                    supplied = (2 * supplied).clamp(1, Self::DEFAULT_MAX_CHOICES);
                 }
+                sample => break sample,
             }
-        }
+        };
+
+        let (value, choices) = match sample {
+            // Zeros the fuzzer didn't consume are kept; replaying them yields the same value.
+            Ok(Some((_, value))) => (value, vec![0; supplied]),
+            Ok(None) | Err(..) => {
+                let (counterexample, traces) = match sample {
+                    Err(FuzzerError::Exited { traces, uplc_error }) => (
+                        Err(uplc_error),
+                        traces
+                            .into_iter()
+                            .filter(|s| PropertyTest::extract_label(s).is_none())
+                            .collect(),
+                    ),
+                    _ => (Ok(None), Vec::new()),
+                };
+
+                return TestResult::PropertyTestResult(PropertyTestResult {
+                    counterexample,
+                    min_successful: 1,
+                    traces,
+                    duration: start.elapsed(),
+                    ..PropertyTestResult::empty(self, None)
+                });
+            }
+        };
+
+        self.run_single(value, choices, 0, None, start)
     }
 
-    /// Try to simplify a 'Counterexample' by manipulating the random sequence of generated values
-    /// (a.k.a. choices). While the implementation is quite involved, the strategy is rather simple
-    /// at least conceptually:
+    /// Evaluate the property against a single value, drawn from the given choices (at the given
+    /// size), and report the outcome as is; the value being the counterexample should the
+    /// property fail.
+    fn run_single<U>(
+        self,
+        value: PlutusData,
+        choices: Vec<u8>,
+        size: usize,
+        seed: Option<u32>,
+        start: Instant,
+    ) -> TestResult<U, PlutusData> {
+        let mut result = self.eval(&value);
+
+        // NOTE: Like in 'run_once', the value is a counterexample whenever the property fails on
+        // it, including for properties expected to fail; see 'is_success'.
+        let failed = has_failed(&result, false);
+
+        let untraced_divergence = failed != has_failed(&self.eval_untraced(&value), false);
+
+        let mut labels = BTreeMap::new();
+
+        Self::collect_labels(&mut result, &mut labels);
+
+        let traces = result
+            .logs()
+            .into_iter()
+            .filter(|s| PropertyTest::extract_label(s).is_none())
+            .collect();
+
+        let (failing_program, scenario) = if failed {
+            (Some(self.apply(&value)), self.scenario(&choices, size))
+        } else {
+            (None, Vec::new())
+        };
+
+        let (counterexample, counterexamples, choices, size, eval_error, failed_check) = if failed {
+            (
+                Some(value.clone()),
+                vec![value],
+                choices,
+                size,
+                result.result().err(),
+                conjunction(&result).and_then(Result::err),
+            )
+        } else {
+            (None, Vec::new(), Vec::new(), 0, None, None)
+        };
+
+        TestResult::PropertyTestResult(PropertyTestResult {
+            counterexample: Ok(counterexample),
+            counterexamples,
+            choices,
+            size,
+            iterations: 1,
+            failed_at: if failed { Some(1) } else { None },
+            labels,
+            traces,
+            duration: start.elapsed(),
+            untraced_divergence,
+            eval_error,
+            failed_check,
+            failing_program,
+            scenario,
+            ..PropertyTestResult::empty(self, seed)
+        })
+    }
+
+    /// Run the property at most `remaining` times, stopping at the first counterexample.
     ///
-    /// Each time a (seeded) fuzzer generates a new value and a new seed, it also stores the
-    /// generated value in a vector, which we call 'choices'. If we re-run the test case with this
-    /// exact choice sequence, we end up with the exact same outcome.
+    /// Iterations are split into chunks of SAMPLING_CHUNK_SIZE, each drawing from an independent
+    /// seeded stream, such that the sampling phase can run in parallel. The first failing chunk (by
+    /// iteration index) is then replayed sequentially to produce and shrink its counterexample;
+    /// labels are collected up to that failing iteration, as if iterations ran one after the other.
     ///
-    /// But, we can tweak chunks of this sequence in hope to generate a _smaller sequence_, thus
-    /// generally resulting in a _smaller counterexample_. Each transformations is applied on
-    /// chunks of size 8, 4, 2 and 1; until we no longer make progress (i.e. hit a fix point).
+    /// Both phases are plain loops; the stack doesn't grow with the number of iterations. The
+    /// first failure wins, and is already shrunk when returned.
     ///
-    /// As per MiniThesis, we consider the following transformations:
+    /// Note that workers do not share any program with the main thread (nor with one another).
+    /// Programs are made of reference-counted terms which cannot be shared across threads. Hence,
+    /// each worker decodes its own copy of the programs from their flat serialisation.
     ///
-    /// - Deleting chunks
-    /// - Transforming chunks into sequence of zeroes
-    /// - Replacing chunks of values with smaller values
-    /// - Sorting chunks in ascending order
-    /// - Swapping nearby pairs
-    /// - Redistributing values between nearby pairs
-    fn simplify(&mut self) {
-        let mut prev;
+    /// Whether the fuzzer stalls (see 'PropertyTestResult::stalled_fuzzer') is told by the first
+    /// value the first chunk draws, at no extra cost.
+    fn run_n_times<'a>(
+        &'a self,
+        remaining: &mut usize,
+        seed: u32,
+        labels: &mut BTreeMap<String, usize>,
+        stalled: &mut bool,
+        limits: &Limits,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) -> Result<Option<Counterexample<'a>>, FuzzerError> {
+        use rayon::prelude::*;
 
-        loop {
-            prev = self.choices.clone();
+        let chunks = (0..*remaining)
+            .step_by(Self::SAMPLING_CHUNK_SIZE)
+            .map(|start| Self::SAMPLING_CHUNK_SIZE.min(*remaining - start))
+            .collect::<Vec<_>>();
 
-            // First try deleting each choice we made in chunks. We try longer chunks because this
-            // allows us to delete whole composite elements: e.g. deleting an element from a
-            // generated list requires us to delete both the choice of whether to include it and
-            // also the element itself, which may involve more than one choice.
-            let mut k = 8;
-            while k > 0 {
-                let (mut i, mut underflow) = if self.choices.len() < k {
-                    (0, true)
-                } else {
-                    (self.choices.len() - k, false)
+        let flat = |program: &Program<NamedDeBruijn>| {
+            Program::<DeBruijn>::from(program.clone())
+                .to_flat()
+                .expect("failed to flat-encode program")
+        };
+
+        let property = flat(&self.compiled.property);
+        let fuzzer = flat(&self.compiled.fuzzer);
+
+        let scans = chunks
+            .par_iter()
+            .enumerate()
+            .map(|(chunk, size)| {
+                let unflat = |bytes: &[u8]| {
+                    Program::<NamedDeBruijn>::from(
+                        Program::<DeBruijn>::from_flat(bytes)
+                            .expect("failed to decode flat-encoded program"),
+                    )
                 };
 
-                while !underflow {
-                    if i >= self.choices.len() {
-                        (i, underflow) = i.overflowing_sub(1);
-                        continue;
-                    }
+                Self::scan(
+                    &unflat(&property),
+                    &unflat(&fuzzer),
+                    Self::chunk_prng(seed, chunk),
+                    *size,
+                    limits.max_choices,
+                )
+            })
+            .collect::<Vec<_>>();
 
-                    let j = i + k;
+        for (chunk, (size, (chunk_labels, interrupted, chunk_stalled))) in
+            chunks.into_iter().zip(scans).enumerate()
+        {
+            if chunk == 0 {
+                *stalled = chunk_stalled;
+            }
 
-                    let mut choices = [
-                        &self.choices[..i],
-                        if j < self.choices.len() {
-                            &self.choices[j..]
-                        } else {
-                            &[]
-                        },
-                    ]
-                    .concat();
+            if interrupted {
+                let mut chunk_remaining = size;
 
-                    if !self.consider(&choices) {
-                        // Perform an extra reduction step that decrease the size of choices near
-                        // the end, to cope with dependencies between choices, e.g. drawing a
-                        // number as a list length, and then drawing that many elements.
-                        //
-                        // This isn't perfect, but allows to make progresses in many cases.
-                        if i > 0 && choices[i - 1] > 0 {
-                            choices[i - 1] -= 1;
-                            if self.consider(&choices) {
-                                i += 1;
-                            };
-                        }
+                let counterexample = self.run_chunk(
+                    &mut chunk_remaining,
+                    Self::chunk_prng(seed, chunk),
+                    labels,
+                    limits,
+                    progress,
+                );
 
-                        (i, underflow) = i.overflowing_sub(1);
-                    }
-                }
+                *remaining -= size - chunk_remaining;
 
-                k /= 2
+                return counterexample;
             }
 
-            if !self.choices.is_empty() {
-                // Now we try replacing region of choices with zeroes. Note that unlike the above we
-                // skip k = 1 because we handle that in the next step. Often (but not always) a block
-                // of all zeroes is the smallest value that a region can be.
-                let mut k = 8;
-                while k > 1 {
-                    let mut i = self.choices.len();
-                    while i >= k {
-                        let ivs = (i - k..i).map(|j| (j, 0)).collect::<Vec<_>>();
-                        i -= if self.replace(ivs) { k } else { 1 }
-                    }
-                    k /= 2
-                }
+            for (label, count) in chunk_labels {
+                labels
+                    .entry(label)
+                    .and_modify(|total| *total += count)
+                    .or_insert(count);
+            }
 
-                // Replace choices with smaller value, by doing a binary search. This will replace n
-                // with 0 or n - 1, if possible, but will also more efficiently replace it with, a
-                // smaller number than doing multiple subtractions would.
-                let (mut i, mut underflow) = (self.choices.len() - 1, false);
-                while !underflow {
-                    self.binary_search_replace(0, self.choices[i], |v| vec![(i, v)]);
-                    (i, underflow) = i.overflowing_sub(1);
-                }
+            *remaining -= size;
+        }
 
-                // Sort out of orders chunks in ascending order
-                let mut k = 8;
-                while k > 1 {
-                    let mut i = self.choices.len() - 1;
-                    while i >= k {
-                        let (from, to) = (i - k, i);
-                        self.replace(
-                            (from..to)
-                                .zip(self.choices[from..to].iter().cloned().sorted())
-                                .collect(),
-                        );
-                        i -= 1;
-                    }
-                    k /= 2
-                }
+        Ok(None)
+    }
 
-                // Try adjusting nearby pairs by:
-                //
-                // - Swapping them if they are out-of-order
-                // - Redistributing values between them.
-                for k in [2, 1] {
-                    let mut j = self.choices.len() - 1;
-                    while j >= k {
-                        let i = j - k;
+    /// Run the property over all its iterations, from the same seeded streams as 'run_n_times',
+    /// but without stopping at the first failure. Counterexamples are shrunk as they're found and
+    /// only distinct ones are kept, stopping once `max_counterexamples` have been collected. Also
+    /// returns the (1-based) iteration at which the first counterexample was found.
+    fn collect_counterexamples<'a>(
+        &'a self,
+        remaining: &mut usize,
+        seed: u32,
+        labels: &mut BTreeMap<String, usize>,
+        max_counterexamples: usize,
+        limits: &Limits,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) -> Result<(Vec<Counterexample<'a>>, Option<usize>), FuzzerError> {
+        let mut counterexamples: Vec<Counterexample<'a>> = Vec::new();
 
-                        // Swap
-                        if self.choices[i] > self.choices[j] {
-                            self.replace(vec![(i, self.choices[j]), (j, self.choices[i])]);
-                        }
+        let n = *remaining;
+        let mut failed_at = None;
 
-                        let iv = self.choices[i];
-                        let jv = self.choices[j];
+        let mut chunk = 0;
 
-                        // Replace
-                        if iv > 0 && jv <= u8::max_value() - iv {
-                            self.binary_search_replace(0, iv, |v| vec![(i, v), (j, jv + (iv - v))]);
-                        }
+        while *remaining > 0 {
+            let mut prng = Self::chunk_prng(seed, chunk);
 
-                        j -= 1
+            for _ in 0..Self::SAMPLING_CHUNK_SIZE.min(*remaining) {
+                let (next_prng, counterexample) = self.run_once(prng, labels, limits, progress)?;
+
+                *remaining -= 1;
+
+                if let Some(counterexample) = counterexample {
+                    failed_at.get_or_insert(n - *remaining);
+
+                    if counterexamples
+                        .iter()
+                        .all(|known| known.value != counterexample.value)
+                    {
+                        counterexamples.push(counterexample);
+                    }
+
+                    if counterexamples.len() >= max_counterexamples {
+                        return Ok((counterexamples, failed_at));
                     }
                 }
-            }
 
-            // If we've reached a fixed point, then we cannot shrink further. We've reached a
-            // (local) minimum, which is as good as a counterexample we'll get with this approach.
-            if prev.as_slice() == self.choices.as_slice() {
-                break;
+                prng = next_prng;
             }
+
+            chunk += 1;
         }
+
+        Ok((counterexamples, failed_at))
     }
 
-    /// Try to replace a value with a smaller value by doing a binary search between
-    /// two extremes. This converges relatively fast in order to shrink down values.
-    fn binary_search_replace<F>(&mut self, lo: u8, hi: u8, f: F) -> u8
-    where
-        F: Fn(u8) -> Vec<(usize, u8)>,
-    {
-        if self.replace(f(lo)) {
-            return lo;
+    /// Resume a run at a given iteration, following the same seeded streams as 'run_n_times',
+    /// and evaluate the property over `n` more iterations only to collect their labels; failures
+    /// are neither shrunk nor reported. This stops early should the fuzzer exit or generate too
+    /// large an input. Returns the number of iterations actually run.
+    fn survey(
+        &self,
+        seed: u32,
+        from: usize,
+        n: usize,
+        labels: &mut BTreeMap<String, usize>,
+        limits: &Limits,
+    ) -> usize {
+        let mut chunk = from / Self::SAMPLING_CHUNK_SIZE;
+        let mut prng = Self::chunk_prng(seed, chunk);
+
+        // Fast-forward through the iterations of that stream which already ran.
+        for _ in 0..from % Self::SAMPLING_CHUNK_SIZE {
+            match prng.sample(&self.compiled.fuzzer) {
+                Ok(Some((next_prng, _))) => prng = next_prng,
+                Ok(None) | Err(..) => return 0,
+            }
         }
 
-        let mut lo = lo;
-        let mut hi = hi;
+        let mut left_in_chunk = Self::SAMPLING_CHUNK_SIZE - from % Self::SAMPLING_CHUNK_SIZE;
+        let mut surveyed = 0;
 
-        while lo + 1 < hi {
-            let mid = lo + (hi - lo) / 2;
-            if self.replace(f(mid)) {
-                hi = mid;
-            } else {
-                lo = mid;
+        while surveyed < n {
+            if left_in_chunk == 0 {
+                chunk += 1;
+                prng = Self::chunk_prng(seed, chunk);
+                left_in_chunk = Self::SAMPLING_CHUNK_SIZE;
+            }
+
+            let (next_prng, value) = match prng.sample(&self.compiled.fuzzer) {
+                Ok(Some(sample)) => sample,
+                Ok(None) | Err(..) => break,
+            };
+
+            if limits.check(next_prng.choices().len()).is_err() {
+                break;
             }
+
+            let mut result = self.eval(&value);
+
+            Self::collect_labels(&mut result, labels);
+
+            prng = next_prng;
+            surveyed += 1;
+            left_in_chunk -= 1;
         }
 
-        hi
+        surveyed
     }
 
-    // Replace values in the choices vector, based on the index-value list provided
-    // and consider the resulting choices.
-    fn replace(&mut self, ivs: Vec<(usize, u8)>) -> bool {
-        let mut choices = self.choices.clone();
+    /// Replay the choices drawn by the fuzzer over the first `iterations` iterations from a seed,
+    /// following the same seeded streams as 'run_n_times'. The property itself isn't evaluated.
+    fn trail(&self, seed: u32, iterations: usize) -> Vec<Vec<u8>> {
+        let mut trail = Vec::with_capacity(iterations);
 
-        for (i, v) in ivs {
-            if i >= choices.len() {
-                return false;
+        let mut chunk = 0;
+
+        while trail.len() < iterations {
+            let mut prng = Self::chunk_prng(seed, chunk);
+
+            for _ in 0..Self::SAMPLING_CHUNK_SIZE.min(iterations - trail.len()) {
+                match prng.sample(&self.compiled.fuzzer) {
+                    Ok(Some((next_prng, _))) => {
+                        trail.push(next_prng.choices());
+                        prng = next_prng;
+                    }
+                    Ok(None) | Err(..) => return trail,
+                }
             }
-            choices[i] = v;
+
+            chunk += 1;
         }
 
-        self.consider(&choices)
+        trail
     }
-}
 
-/// ----- Cache -----------------------------------------------------------------------
-///
-/// A simple cache as a Patricia-trie to look for already explored options. The simplification
-/// steps does often generate the same paths and the generation of new test values as well as the
-/// properties can take a significant time.
-///
-/// Yet, sequences have interesting properties:
-///
-/// 1. The generation and test execution is entirely deterministic.
-///
-///
-pub struct Cache<'a, T> {
-    db: PatriciaMap<Status<T>>,
-    #[allow(clippy::type_complexity)]
-    run: Box<dyn Fn(&[u8]) -> Status<T> + 'a>,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Status<T> {
-    Keep(T),
-    Ignore,
-    Invalid,
-}
+    /// Construct the Prng of an independent sampling stream. Its size starts at the index of the
+    /// first iteration it covers, so that sizes keep growing across streams.
+    fn chunk_prng(seed: u32, chunk: usize) -> Prng {
+        Prng::from_seed(
+            Self::chunk_seed(seed, chunk),
+            chunk * Self::SAMPLING_CHUNK_SIZE,
+        )
+    }
 
-impl<'a, T> Cache<'a, T>
-where
-    T: PartialEq + Clone,
-{
-    pub fn new<F>(run: F) -> Cache<'a, T>
-    where
-        F: Fn(&[u8]) -> Status<T> + 'a,
-    {
-        Cache {
-            db: PatriciaMap::new(),
-            run: Box::new(run),
+    /// Derive the seed of an independent sampling stream. The first stream always uses the
+    /// initial seed.
+    fn chunk_seed(seed: u32, chunk: usize) -> u32 {
+        if chunk == 0 {
+            return seed;
         }
-    }
 
-    pub fn size(&self) -> usize {
-        self.db.len()
+        let mut digest = [0u8; 32];
+        let mut context = Blake2b::new(32);
+        context.input(&seed.to_be_bytes()[..]);
+        context.input(&(chunk as u64).to_be_bytes()[..]);
+        context.result(&mut digest);
+
+        u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]])
     }
 
-    pub fn get(&mut self, choices: &[u8]) -> Status<T> {
-        if let Some((prefix, status)) = self.db.get_longest_common_prefix(choices) {
-            let status = status.clone();
-            if status != Status::Invalid || prefix == choices {
-                return status;
-            }
-        }
+    /// Sample and evaluate a property `n` times from a given Prng, collecting labels. Returns
+    /// whether the scan was interrupted, either because of a failure or because the fuzzer exited
+    /// unexpectedly; and whether the fuzzer handed back the seed of its first draw (see
+    /// 'Prng::sample_checking_seed'). This operates on bare programs so it can run on any thread.
+    fn scan(
+        property: &Program<NamedDeBruijn>,
+        fuzzer: &Program<NamedDeBruijn>,
+        initial_prng: Prng,
+        n: usize,
+        max_choices: usize,
+    ) -> (BTreeMap<String, usize>, bool, bool) {
+        let mut labels = BTreeMap::new();
+        let mut prng = initial_prng;
+        let mut stalled = false;
+
+        for ix in 0..n {
+            let sample = if ix == 0 {
+                prng.sample_checking_seed(fuzzer).map(|sample| {
+                    sample.map(|(next_prng, value, stalls)| {
+                        stalled = stalls;
+                        (next_prng, value)
+                    })
+                })
+            } else {
+                prng.sample(fuzzer)
+            };
 
-        let status = self.run.deref()(choices);
+            let (next_prng, value) = match sample {
+                Ok(Some((next_prng, _))) if next_prng.choices().len() > max_choices => {
+                    return (labels, true, stalled)
+                }
+                Ok(Some(sample)) => sample,
+                Ok(None) | Err(..) => return (labels, true, stalled),
+            };
 
-        // Clear longer path on non-invalid cases, as we will never reach them
-        // again due to a now-shorter prefix found.
-        //
-        // This hopefully keeps the cache under a reasonable size as we prune
-        // the tree as we discover shorter paths.
-        if status != Status::Invalid {
-            let keys = self
-                .db
-                .iter_prefix(choices)
-                .map(|(k, _)| k)
-                .collect::<Vec<_>>();
-            for k in keys {
-                self.db.remove(k);
+            let mut result = Self::eval_program(property, &value);
+
+            Self::collect_labels(&mut result, &mut labels);
+
+            if has_failed(&result, false) {
+                return (labels, true, stalled);
             }
+
+            prng = next_prng;
         }
 
-        self.db.insert(choices, status.clone());
+        (labels, false, stalled)
+    }
 
-        status
+    fn run_chunk<'a>(
+        &'a self,
+        remaining: &mut usize,
+        initial_prng: Prng,
+        labels: &mut BTreeMap<String, usize>,
+        limits: &Limits,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) -> Result<Option<Counterexample<'a>>, FuzzerError> {
+        let mut prng = initial_prng;
+        let mut counterexample = None;
+
+        while *remaining > 0 && counterexample.is_none() {
+            (prng, counterexample) = self.run_once(prng, labels, limits, progress)?;
+            *remaining -= 1;
+        }
+
+        Ok(counterexample)
     }
-}
 
-// ----------------------------------------------------------------------------
-//
-// TestResult
-//
-// ----------------------------------------------------------------------------
+    fn run_once(
+        &self,
+        prng: Prng,
+        labels: &mut BTreeMap<String, usize>,
+        limits: &Limits,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) -> Result<(Prng, Option<Counterexample<'_>>), FuzzerError> {
+        let (next_prng, value) = prng
+            .sample(&self.compiled.fuzzer)?
+            .expect("A seeded PRNG returned 'None' which indicates a fuzzer is ill-formed and implemented wrongly; please contact library's authors.");
 
-#[derive(Debug)]
-pub enum TestResult<U, T> {
-    UnitTestResult(UnitTestResult<U>),
-    PropertyTestResult(PropertyTestResult<T>),
-}
+        let choices = next_prng.choices();
 
-unsafe impl<U, T> Send for TestResult<U, T> {}
+        limits.check(choices.len())?;
 
-impl TestResult<(Constant, Rc<Type>), PlutusData> {
-    pub fn reify(
-        self,
-        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
-    ) -> TestResult<UntypedExpr, UntypedExpr> {
-        match self {
-            TestResult::UnitTestResult(test) => TestResult::UnitTestResult(test.reify(data_types)),
-            TestResult::PropertyTestResult(test) => {
-                TestResult::PropertyTestResult(test.reify(data_types))
-            }
-        }
-    }
-}
+        let mut result = self.eval(&value);
 
-impl<U, T> TestResult<U, T> {
-    pub fn is_success(&self) -> bool {
-        match self {
-            TestResult::UnitTestResult(UnitTestResult { success, .. }) => *success,
-            TestResult::PropertyTestResult(PropertyTestResult {
-                counterexample: Err(..),
-                ..
-            }) => false,
-            TestResult::PropertyTestResult(PropertyTestResult {
-                counterexample: Ok(counterexample),
-                test,
-                ..
-            }) => {
-                if test.can_error {
-                    counterexample.is_some()
-                } else {
-                    counterexample.is_none()
-                }
-            }
-        }
-    }
+        Self::collect_labels(&mut result, labels);
 
-    pub fn module(&self) -> &str {
-        match self {
-            TestResult::UnitTestResult(UnitTestResult { ref test, .. }) => test.module.as_str(),
-            TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
-                test.module.as_str()
-            }
+        // NOTE: We do NOT pass self.can_error here, because when searching for
+        // failing properties, we do want to _keep running_ until we find a
+        // a failing case. It may not occur on the first run.
+        if has_failed(&result, false) {
+            let counterexample = self.shrink_with(value, choices, prng.size(), limits, progress);
+            Ok((next_prng, Some(counterexample)))
+        } else {
+            Ok((next_prng, None))
         }
     }
 
-    pub fn title(&self) -> &str {
-        match self {
-            TestResult::UnitTestResult(UnitTestResult { ref test, .. }) => test.name.as_str(),
-            TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
-                test.name.as_str()
+    /// Replay each entry of a corpus, in order. Entries the fuzzer can't replay are skipped. The
+    /// first failing entry is shrunk right away and ends the run. Also returns the number of
+    /// entries replayed.
+    fn run_corpus<'a>(
+        &'a self,
+        corpus: &[Vec<u8>],
+        labels: &mut BTreeMap<String, usize>,
+        limits: &Limits,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) -> Result<(usize, Option<Counterexample<'a>>), FuzzerError> {
+        let mut replayed = 0;
+
+        for choices in corpus {
+            limits.check(choices.len())?;
+
+            let value = match Prng::from_choices(choices).sample(&self.compiled.fuzzer)? {
+                Some((_, value)) => value,
+                None => continue,
+            };
+
+            replayed += 1;
+
+            let mut result = self.eval(&value);
+
+            Self::collect_labels(&mut result, labels);
+
+            if has_failed(&result, false) {
+                let counterexample = self.shrink_with(value, choices.clone(), 0, limits, progress);
+                return Ok((replayed, Some(counterexample)));
             }
         }
+
+        Ok((replayed, None))
     }
 
-    pub fn traces(&self) -> &[String] {
-        match self {
-            TestResult::UnitTestResult(UnitTestResult { ref traces, .. })
-            | TestResult::PropertyTestResult(PropertyTestResult { ref traces, .. }) => {
-                traces.as_slice()
-            }
+    /// Shrink a known failing input, given as the choices leading the fuzzer to it, without
+    /// searching for one first. Returns the simplified choices along with the value they lead to;
+    /// or 'None' when the choices don't lead to a counterexample in the first place (including
+    /// when the fuzzer can't replay them). Choices are replayed at size 0.
+    pub fn shrink(&self, choices: Vec<u8>) -> Option<(Vec<u8>, PlutusData)> {
+        let value = match Prng::from_choices(&choices).sample(&self.compiled.fuzzer) {
+            Ok(Some((_, value))) => value,
+            Ok(None) | Err(..) => return None,
+        };
+
+        if self.verdict(&value) == self.can_error {
+            return None;
         }
+
+        let counterexample = self.shrink_with(value, choices, 0, &Limits::default(), &mut |_| {});
+
+        Some((counterexample.choices, counterexample.value))
     }
 
-    pub fn into_error(&self, verbose: bool) -> crate::Error {
-        let (name, path, src) = match self {
-            TestResult::UnitTestResult(UnitTestResult { test, .. }) => (
-                test.name.to_string(),
-                test.input_path.to_path_buf(),
-                test.program.to_pretty(),
-            ),
-            TestResult::PropertyTestResult(PropertyTestResult { test, .. }) => (
-                test.name.to_string(),
-                test.input_path.to_path_buf(),
-                test.program.to_pretty(),
-            ),
-        };
-        crate::Error::TestFailure {
-            name,
-            path,
-            src,
-            verbose,
+    /// Turn a failing value, drawn from the given choices at the given size, into a
+    /// counterexample; simplified as much as possible.
+    fn shrink_with(
+        &self,
+        value: PlutusData,
+        choices: Vec<u8>,
+        size: usize,
+        limits: &Limits,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) -> Counterexample<'_> {
+        let mut counterexample = Counterexample::sized(self, value, choices, size);
+
+        counterexample.schedule = limits.shrink_schedule.clone();
+
+        if !counterexample.choices.is_empty() {
+            counterexample.simplify_with(limits.shrink_timeout, progress);
         }
+
+        counterexample
     }
-}
 
-#[derive(Debug)]
-pub struct UnitTestResult<T> {
-    pub success: bool,
-    pub spent_budget: ExBudget,
-    pub traces: Vec<String>,
-    pub test: UnitTest,
-    pub assertion: Option<Assertion<T>>,
-}
+    /// Run the property at most `n` times from a seed, and reify the (simplified) counterexample
+    /// it yields, if any. This makes it easy to assert what a given seed shrinks to.
+    pub fn reified_counterexample(
+        &self,
+        seed: u32,
+        n: usize,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+    ) -> Option<UntypedExpr> {
+        let mut labels = BTreeMap::new();
+        let mut remaining = n;
 
-unsafe impl<T> Send for UnitTestResult<T> {}
+        let limits = &Limits::default();
 
-impl UnitTestResult<(Constant, Rc<Type>)> {
-    pub fn reify(
-        self,
+        let counterexample = self
+            .run_n_times(
+                &mut remaining,
+                seed,
+                &mut labels,
+                &mut false,
+                limits,
+                &mut |_| {},
+            )
+            .ok()??;
+
+        Some(
+            UntypedExpr::reify_data(
+                data_types,
+                counterexample.value.clone(),
+                &self.fuzzer.type_info,
+            )
+            .unwrap_or_else(|_| UntypedExpr::reify_blind(counterexample.value)),
+        )
+    }
+
+    /// Evaluate the property against a hand-picked Aiken value, of the fuzzer's type. This is the
+    /// inverse of reifying counterexamples: the value is first converted to Plutus data.
+    pub fn check_value(
+        &self,
+        value: &UntypedExpr,
         data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
-    ) -> UnitTestResult<UntypedExpr> {
-        UnitTestResult {
-            success: self.success,
-            spent_budget: self.spent_budget,
-            traces: self.traces,
-            test: self.test,
-            assertion: self.assertion.and_then(|assertion| {
-                // No need to spend time/cpu on reifying assertions for successful
-                // tests since they aren't shown.
-                if self.success {
-                    return None;
-                }
+    ) -> Result<EvalResult, String> {
+        let value = value.to_data(data_types, &self.fuzzer.type_info)?;
+        Ok(self.eval(&value))
+    }
 
-                Some(Assertion {
-                    bin_op: assertion.bin_op,
-                    head: assertion.head.map(|(cst, tipo)| {
-                        UntypedExpr::reify_constant(data_types, cst, &tipo)
-                            .expect("failed to reify assertion operand?")
-                    }),
-                    tail: assertion.tail.map(|xs| {
-                        xs.mapped(|(cst, tipo)| {
-                            UntypedExpr::reify_constant(data_types, cst, &tipo)
-                                .expect("failed to reify assertion operand?")
-                        })
-                    }),
-                })
-            }),
+    /// Replay the choices of a counterexample through the fuzzer, at the size they were drawn at,
+    /// and tell whether they lead back to the same value; on which the property must still fail.
+    /// Shrinking relies on choices alone to regenerate values, which fuzzers behaving differently
+    /// when replayed (e.g. depending on whether their PRNG is seeded) defeat.
+    fn is_deterministic(&self, counterexample: &Counterexample<'_>) -> bool {
+        match Prng::from_choices(&counterexample.choices)
+            .with_size(counterexample.size)
+            .sample(&self.compiled.fuzzer)
+        {
+            Ok(Some((_, value))) => {
+                value == counterexample.value && self.verdict(&value) != self.can_error
+            }
+            Ok(None) | Err(..) => false,
         }
     }
-}
 
-#[derive(Debug)]
-pub struct PropertyTestResult<T> {
-    pub test: PropertyTest,
-    pub counterexample: Result<Option<T>, uplc::machine::Error>,
-    pub iterations: usize,
-    pub labels: BTreeMap<String, usize>,
-    pub traces: Vec<String>,
-}
+    pub fn eval(&self, value: &PlutusData) -> EvalResult {
+        Self::eval_program(&self.compiled.property, value)
+    }
 
-unsafe impl<T> Send for PropertyTestResult<T> {}
+    /// Draw a single value from the given seed, and evaluate the property against it without
+    /// passing any verdict: the value and the evaluation result (term, logs and budget) are
+    /// returned as is, for callers to make their own. Yields nothing when the fuzzer doesn't.
+    pub fn run_raw(&self, seed: u32) -> Result<Option<(PlutusData, EvalResult)>, FuzzerError> {
+        Ok(Prng::from_seed(seed, 0)
+            .sample(&self.compiled.fuzzer)?
+            .map(|(_, value)| {
+                let result = self.eval(&value);
+                (value, result)
+            }))
+    }
 
-impl PropertyTestResult<PlutusData> {
-    pub fn reify(
-        self,
-        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
-    ) -> PropertyTestResult<UntypedExpr> {
-        PropertyTestResult {
-            counterexample: self.counterexample.map(|ok| {
-                ok.map(|counterexample| {
-                    UntypedExpr::reify_data(data_types, counterexample, &self.test.fuzzer.type_info)
-                        .expect("Failed to reify counterexample?")
-                })
-            }),
-            iterations: self.iterations,
-            test: self.test,
-            labels: self.labels,
-            traces: self.traces,
+    /// Evaluate the property against a value and only tell whether it failed. This is what
+    /// drives shrinking: logs emitted during the evaluation are dropped right away (see
+    /// 'Program::eval_without_logs') and never take part in accepting or rejecting a candidate.
+    /// Logs are only captured once for the final counterexample.
+    fn verdict(&self, value: &PlutusData) -> bool {
+        let result = self
+            .compiled
+            .property
+            .apply_data(value.clone())
+            .eval_without_logs(ExBudget::max());
+
+        has_failed(&result, self.can_error)
+    }
+
+    fn eval_program(program: &Program<NamedDeBruijn>, value: &PlutusData) -> EvalResult {
+        program.apply_data(value.clone()).eval(ExBudget::max())
+    }
+
+    /// The fuzzer's own narration of the value drawn from the given choices (at the given size):
+    /// whatever it traced while generating it, labels aside. Empty for fuzzers that don't trace
+    /// anything.
+    pub fn scenario(&self, choices: &[u8], size: usize) -> Vec<String> {
+        match Prng::from_choices(choices)
+            .with_size(size)
+            .sample_with_traces(&self.compiled.fuzzer)
+        {
+            Ok(Some((_, _, traces))) => traces
+                .into_iter()
+                .filter(|s| PropertyTest::extract_label(s).is_none())
+                .collect(),
+            Ok(None) | Err(..) => Vec::new(),
         }
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct Assertion<T> {
-    pub bin_op: BinOp,
-    pub head: Result<T, ()>,
-    pub tail: Result<Vec1<T>, ()>,
+    /// The property applied to a value, as evaluated by 'eval'.
+    pub fn apply(&self, value: &PlutusData) -> Program<NamedDeBruijn> {
+        self.compiled.property.apply_data(value.clone())
+    }
+
+    fn collect_labels(result: &mut EvalResult, labels: &mut BTreeMap<String, usize>) {
+        for s in result.logs() {
+            // NOTE: There may be other log outputs that interefere with labels. So *by
+            // convention*, we treat as label strings that starts with a NUL byte, which
+            // should be a guard sufficient to prevent inadvertent clashes.
+            if let Some(label) = PropertyTest::extract_label(&s) {
+                labels
+                    .entry(label)
+                    .and_modify(|count| *count += 1)
+                    .or_insert(1);
+            }
+        }
+    }
+
+    /// Evaluate the property against a value, as if it had been compiled without any trace. Traces
+    /// are erased from the compiled program directly: every application of the 'trace' builtin is
+    /// replaced by its second argument, without evaluating the message.
+    ///
+    /// The outcome may therefore differ from 'eval' when evaluating a trace message fails (e.g. a
+    /// message built from a partial function), or when the message evaluation exhausts the budget.
+    /// Such properties only fail because of their instrumentation and not because of their logic.
+    pub fn eval_untraced(&self, value: &PlutusData) -> EvalResult {
+        Self::eval_program(&self.compiled.untraced, value)
+    }
+
+    fn extract_label(s: &str) -> Option<String> {
+        if s.starts_with('\0') {
+            Some(s.split_at(1).1.to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Remove all traces from a term. The code generator hoists the 'trace' builtin as an argument to
+/// an outer lambda, so we keep track of variables bound to it in order to also erase applications
+/// of those variables.
+fn erase_traces(term: &Term<Name>) -> Term<Name> {
+    /// Work left to do: either a term to visit (with the variables bound to 'trace' in its scope),
+    /// or a term to rebuild from the last erased ones, once all its sub-terms have been erased.
+    enum Step<'a> {
+        Visit(&'a Term<Name>, Rc<Vec<Name>>),
+        Apply,
+        TraceBinding(&'a Rc<Name>, &'a Rc<Term<Name>>),
+        Delay,
+        Force,
+        Lambda(&'a Rc<Name>),
+        Constr(usize, usize),
+        Case(usize),
+    }
+
+    let is_trace = |term: &Term<Name>, trace_vars: &[Name]| match term {
+        Term::Force(inner) => matches!(inner.as_ref(), Term::Builtin(DefaultFunction::Trace)),
+        Term::Var(name) => trace_vars.contains(name),
+        _ => false,
+    };
+
+    let mut steps = vec![Step::Visit(term, Rc::new(Vec::new()))];
+    let mut erased: Vec<Term<Name>> = Vec::new();
+
+    // Like in 'term_size', programs can be quite deep, so we walk them with an explicit stack
+    // rather than recursively. Sub-terms are visited first to last, and rebuilt from 'erased'.
+    while let Some(step) = steps.pop() {
+        match step {
+            Step::Visit(term, trace_vars) => match term {
+                Term::Apply { function, argument } => match function.as_ref() {
+                    Term::Apply {
+                        function: trace, ..
+                    } if is_trace(trace, &trace_vars) => {
+                        steps.push(Step::Visit(argument, trace_vars));
+                    }
+                    Term::Lambda {
+                        parameter_name,
+                        body,
+                    } if is_trace(argument, &trace_vars) => {
+                        let mut trace_vars = trace_vars.to_vec();
+                        trace_vars.push(parameter_name.as_ref().clone());
+                        steps.push(Step::TraceBinding(parameter_name, argument));
+                        steps.push(Step::Visit(body, Rc::new(trace_vars)));
+                    }
+                    _ => {
+                        steps.push(Step::Apply);
+                        steps.push(Step::Visit(argument, trace_vars.clone()));
+                        steps.push(Step::Visit(function, trace_vars));
+                    }
+                },
+                Term::Delay(then) => {
+                    steps.push(Step::Delay);
+                    steps.push(Step::Visit(then, trace_vars));
+                }
+                Term::Force(then) => {
+                    steps.push(Step::Force);
+                    steps.push(Step::Visit(then, trace_vars));
+                }
+                Term::Lambda {
+                    parameter_name,
+                    body,
+                } => {
+                    steps.push(Step::Lambda(parameter_name));
+                    steps.push(Step::Visit(body, trace_vars));
+                }
+                Term::Constr { tag, fields } => {
+                    steps.push(Step::Constr(*tag, fields.len()));
+                    steps.extend(
+                        fields
+                            .iter()
+                            .rev()
+                            .map(|field| Step::Visit(field, trace_vars.clone())),
+                    );
+                }
+                Term::Case { constr, branches } => {
+                    steps.push(Step::Case(branches.len()));
+                    steps.extend(
+                        branches
+                            .iter()
+                            .rev()
+                            .map(|branch| Step::Visit(branch, trace_vars.clone())),
+                    );
+                    steps.push(Step::Visit(constr, trace_vars));
+                }
+                Term::Var(..) | Term::Constant(..) | Term::Error | Term::Builtin(..) => {
+                    erased.push(term.clone());
+                }
+            },
+            Step::Apply => {
+                let argument = erased.pop().expect("erased argument");
+                let function = erased.pop().expect("erased function");
+                erased.push(Term::Apply {
+                    function: function.into(),
+                    argument: argument.into(),
+                });
+            }
+            Step::TraceBinding(parameter_name, argument) => {
+                let body = erased.pop().expect("erased body");
+                erased.push(Term::Apply {
+                    function: Term::Lambda {
+                        parameter_name: parameter_name.clone(),
+                        body: body.into(),
+                    }
+                    .into(),
+                    argument: argument.clone(),
+                });
+            }
+            Step::Delay => {
+                let then = erased.pop().expect("erased term");
+                erased.push(Term::Delay(then.into()));
+            }
+            Step::Force => {
+                let then = erased.pop().expect("erased term");
+                erased.push(Term::Force(then.into()));
+            }
+            Step::Lambda(parameter_name) => {
+                let body = erased.pop().expect("erased body");
+                erased.push(Term::Lambda {
+                    parameter_name: parameter_name.clone(),
+                    body: body.into(),
+                });
+            }
+            Step::Constr(tag, arity) => {
+                let fields = erased.split_off(erased.len() - arity);
+                erased.push(Term::Constr { tag, fields });
+            }
+            Step::Case(arity) => {
+                let branches = erased.split_off(erased.len() - arity);
+                let constr = erased.pop().expect("erased constr");
+                erased.push(Term::Case {
+                    constr: constr.into(),
+                    branches,
+                });
+            }
+        }
+    }
+
+    erased.pop().expect("erased term")
+}
+
+/// ----- ChoiceSource -----------------------------------------------------------------
+///
+/// A stream of choices, from which a Prng can be constructed (see 'Prng::from_source'). Any
+/// iterator of bytes is one; 'HashedChoices' is a pseudo-random one.
+pub trait ChoiceSource {
+    /// The next choice of the stream, or 'None' once exhausted.
+    fn next_choice(&mut self) -> Option<u8>;
+}
+
+impl<I: Iterator<Item = u8>> ChoiceSource for I {
+    fn next_choice(&mut self) -> Option<u8> {
+        self.next()
+    }
+}
+
+/// An endless stream of pseudo-random choices, derived from a seed by chaining blake2b hashes.
+/// Unlike seeded fuzzers, the choices are drawn by the framework itself.
+#[derive(Debug, Clone)]
+pub struct HashedChoices {
+    digest: [u8; 32],
+    cursor: usize,
+}
+
+impl HashedChoices {
+    pub fn new(seed: u32) -> Self {
+        let mut digest = [0u8; 32];
+        let mut context = Blake2b::new(32);
+        context.input(&seed.to_be_bytes()[..]);
+        context.result(&mut digest);
+
+        HashedChoices { digest, cursor: 0 }
+    }
+}
+
+impl Iterator for HashedChoices {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.cursor == self.digest.len() {
+            let mut context = Blake2b::new(32);
+            context.input(&self.digest[..]);
+            context.result(&mut self.digest);
+            self.cursor = 0;
+        }
+
+        self.cursor += 1;
+
+        Some(self.digest[self.cursor - 1])
+    }
+}
+
+/// ----- PRNG -----------------------------------------------------------------
+///
+/// A Pseudo-random generator (PRNG) used to produce random values for fuzzers.
+/// Note that the randomness isn't actually managed by the Rust framework, it
+/// entirely relies on properties of hashing algorithm on-chain (e.g. blake2b).
+///
+/// The PRNG can have two forms:
+///
+/// 1. Seeded: which occurs during the initial run of a property. Each time a
+///    number is drawn from the PRNG, a new seed is created. We retain all the
+///    choices drawn in a _choices_ vector. It also carries a _size_, which grows
+///    with each iteration and which fuzzers may use as a hint to generate small
+///    values early and larger ones later on.
+///
+/// 2. Replayed: which is used to replay a Prng sequenced from a list of known
+///    choices. This happens when shrinking an example. Instead of trying to
+///    shrink the value directly, we shrink the PRNG sequence with the hope that
+///    it will generate a smaller value. This implies that generators tend to
+///    generate smaller values when drawing smaller numbers. It carries the size
+///    the choices were first drawn at, so that fuzzers scaling with the size
+///    regenerate the same value from them.
+///
+/// PRNGs built before sizes were introduced (i.e. with only two fields) are
+/// still accepted, and count as being of size 0.
+///
+/// Choices are unsigned bytes, for they are stored on-chain as a bytearray. Fuzzers
+/// of signed values should therefore map choices through a zig-zag encoding (i.e.
+/// 0, -1, 1, -2, 2, ...), so that shrinking choices towards zero shrinks values
+/// towards zero from either side.
+///
+#[derive(Debug)]
+pub enum Prng {
+    Seeded { choices: Vec<u8>, uplc: PlutusData },
+    Replayed { choices: Vec<u8>, uplc: PlutusData },
 }
 
-impl TryFrom<TypedExpr> for Assertion<TypedExpr> {
-    type Error = ();
+impl Prng {
+    /// Constructor tag for Prng's 'Seeded'
+    const SEEDED: u64 = 0;
+    /// Constructor tag for Prng's 'Replayed'
+    const REPLAYED: u64 = 1;
+
+    /// Constructor tag for Option's 'Some'
+    const SOME: u64 = 0;
+    /// Constructor tag for Option's 'None'
+    const NONE: u64 = 1;
+
+    pub fn uplc(&self) -> PlutusData {
+        match self {
+            Prng::Seeded { uplc, .. } => uplc.clone(),
+            Prng::Replayed { uplc, .. } => uplc.clone(),
+        }
+    }
+
+    /// The choices drawn so far, in the order they were drawn. Choices are the individual bytes
+    /// of the Prng's on-chain bytearray, so any fuzzer output fits in a 'u8' by construction.
+    pub fn choices(&self) -> Vec<u8> {
+        match self {
+            Prng::Seeded { choices, .. } => {
+                let mut choices = choices.to_vec();
+                choices.reverse();
+                choices
+            }
+            Prng::Replayed { choices, .. } => choices.to_vec(),
+        }
+    }
+
+    /// The size hint carried by the Prng; see 'from_seed'.
+    pub fn size(&self) -> usize {
+        let fields = match self {
+            Prng::Seeded { uplc, .. } => Prng::constr_fields(uplc, Prng::SEEDED),
+            Prng::Replayed { uplc, .. } => Prng::constr_fields(uplc, Prng::REPLAYED),
+        };
+
+        match fields {
+            Some([_, _, PlutusData::BigInt(size)]) => {
+                usize::try_from(&uplc::machine::value::from_pallas_bigint(size)).unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
+    /// The same Prng, but carrying the given size hint instead. This is how choices drawn at
+    /// some size are replayed at that size (see 'from_choices').
+    pub fn with_size(self, size: usize) -> Prng {
+        let (tag, choices, uplc) = match self {
+            Prng::Seeded { choices, uplc } => (Prng::SEEDED, choices, uplc),
+            Prng::Replayed { choices, uplc } => (Prng::REPLAYED, choices, uplc),
+        };
+
+        let uplc = match Prng::constr_fields(&uplc, tag) {
+            Some([first, second, ..]) => Data::constr(
+                tag,
+                vec![first.clone(), second.clone(), Data::integer(size.into())],
+            ),
+            _ => uplc,
+        };
+
+        if tag == Prng::SEEDED {
+            Prng::Seeded { choices, uplc }
+        } else {
+            Prng::Replayed { choices, uplc }
+        }
+    }
+
+    /// Number of choices a replayed Prng has yet to hand out; seeded ones never run out.
+    pub fn remaining(&self) -> usize {
+        match self {
+            Prng::Seeded { .. } => usize::MAX,
+            Prng::Replayed { uplc, .. } => match Prng::constr_fields(uplc, Prng::REPLAYED) {
+                Some([PlutusData::BigInt(cursor), ..]) => {
+                    usize::try_from(&uplc::machine::value::from_pallas_bigint(cursor)).unwrap_or(0)
+                }
+                _ => 0,
+            },
+        }
+    }
+
+    /// Construct a Pseudo-random number generator from a seed, and an initial size.
+    pub fn from_seed(seed: u32, size: usize) -> Prng {
+        Prng::from_seed_and_choices(seed, size, &[])
+    }
+
+    /// Construct a seeded Pseudo-random number generator whose choices are primed with a known
+    /// prefix. Values are still drawn from the seed; the prefix merely leads the recorded choices.
+    /// Replaying those choices (see 'from_choices') thus goes through the prefix first, then
+    /// through whatever got drawn after it.
+    pub fn from_seed_and_choices(seed: u32, size: usize, prefix: &[u8]) -> Prng {
+        let mut digest = [0u8; 32];
+        let mut context = Blake2b::new(32);
+        context.input(&seed.to_be_bytes()[..]);
+        context.result(&mut digest);
+
+        // Seeded choices are kept in reverse order, as fuzzers prepend them on-chain.
+        let choices = prefix.iter().rev().cloned().collect::<Vec<_>>();
+
+        Prng::Seeded {
+            uplc: Data::constr(
+                Prng::SEEDED,
+                vec![
+                    Data::bytestring(digest.to_vec()), // Prng's seed
+                    Data::bytestring(choices.clone()), // Random choices
+                    Data::integer(size.into()),        // Size
+                ],
+            ),
+            choices,
+        }
+    }
+
+    /// Construct a Pseudo-random number generator from a label (e.g. a test name) and a base
+    /// seed. Different labels yield independent streams, which remain reproducible from the
+    /// same base seed.
+    pub fn from_label(label: &str, base_seed: u32) -> Prng {
+        Prng::from_seed(Prng::label_seed(label, base_seed), 0)
+    }
+
+    /// Derive the seed used by 'from_label'.
+    pub fn label_seed(label: &str, base_seed: u32) -> u32 {
+        let mut digest = [0u8; 32];
+        let mut context = Blake2b::new(32);
+        context.input(&base_seed.to_be_bytes()[..]);
+        context.input(label.as_bytes());
+        context.result(&mut digest);
+
+        u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]])
+    }
+
+    /// Construct a Pseudo-random number generator from a pre-defined list of choices, of size 0.
+    /// See 'with_size' to replay choices drawn at another size.
+    pub fn from_choices(choices: &[u8]) -> Prng {
+        Prng::from_source(&mut choices.iter().copied(), choices.len())
+    }
+
+    /// Construct a Pseudo-random number generator replaying (at most) the first `n` choices of a
+    /// source. This lets the framework, or advanced users, control the stream of choices fuzzers
+    /// draw from; instead of letting fuzzers derive it from a seed.
+    pub fn from_source(source: &mut impl ChoiceSource, n: usize) -> Prng {
+        let choices = (0..n)
+            .map_while(|_| source.next_choice())
+            .collect::<Vec<_>>();
+
+        Prng::Replayed {
+            uplc: Data::constr(
+                Prng::REPLAYED,
+                vec![
+                    Data::integer(choices.len().into()),
+                    Data::bytestring(choices.iter().rev().cloned().collect::<Vec<_>>()),
+                    Data::integer(0.into()),
+                ],
+            ),
+            choices,
+        }
+    }
+
+    /// Generate a pseudo-random value from a fuzzer using the given PRNG. When seeded, the seed of
+    /// the next PRNG is derived from the current one, regardless of the seed returned by the
+    /// fuzzer. This way, iterations are independent even when a fuzzer fails to advance its seed.
+    pub fn sample(
+        &self,
+        fuzzer: &Program<NamedDeBruijn>,
+    ) -> Result<Option<(Prng, PlutusData)>, FuzzerError> {
+        Ok(self
+            .sample_with_traces(fuzzer)?
+            .map(|(next_prng, value, _)| (next_prng, value)))
+    }
+
+    /// Same as 'sample', but also yields the traces emitted by the fuzzer while generating the
+    /// value. Fuzzers may use those to narrate the scenario behind the value (e.g. "user with
+    /// zero balance"), which the value alone doesn't always tell.
+    pub fn sample_with_traces(
+        &self,
+        fuzzer: &Program<NamedDeBruijn>,
+    ) -> Result<Option<(Prng, PlutusData, Vec<String>)>, FuzzerError> {
+        let mut result = fuzzer.apply_data(self.uplc()).eval(ExBudget::max());
+
+        let traces = result.logs();
+
+        match result.result().and_then(Prng::from_result) {
+            Ok(drawn) => {
+                Ok(drawn.map(|(next_prng, value)| (self.reseed(next_prng), value, traces)))
+            }
+            Err(uplc_error) => Err(FuzzerError::Exited { traces, uplc_error }),
+        }
+    }
+
+    /// The seed of a seeded PRNG.
+    fn seed(&self) -> Option<&[u8]> {
+        match self {
+            Prng::Seeded {
+                uplc: PlutusData::Constr(Constr { fields, .. }),
+                ..
+            } => match fields.first() {
+                Some(PlutusData::BoundedBytes(seed)) => Some(seed),
+                _ => None,
+            },
+            Prng::Seeded { .. } | Prng::Replayed { .. } => None,
+        }
+    }
+
+    /// Replace the seed of a PRNG obtained by sampling from 'self', with one derived from the seed
+    /// of 'self'. The seed is hashed with a tag, so that it never collides with seeds a fuzzer
+    /// may derive itself by hashing.
+    fn reseed(&self, next_prng: Prng) -> Prng {
+        match (self.seed(), next_prng) {
+            (
+                Some(seed),
+                Prng::Seeded {
+                    choices,
+                    uplc:
+                        PlutusData::Constr(Constr {
+                            tag,
+                            any_constructor,
+                            mut fields,
+                        }),
+                },
+            ) if !fields.is_empty() => {
+                let mut digest = [0u8; 32];
+                let mut context = Blake2b::new(32);
+                context.input(seed);
+                context.input(b"next");
+                context.result(&mut digest);
+
+                fields[0] = Data::bytestring(digest.to_vec());
+
+                Prng::Seeded {
+                    choices,
+                    uplc: PlutusData::Constr(Constr {
+                        tag,
+                        any_constructor,
+                        fields,
+                    }),
+                }
+            }
+            (_, next_prng) => next_prng,
+        }
+    }
+
+    /// Same as 'sample', but also tells whether the fuzzer drew choices from the seed it was
+    /// given without advancing it; i.e. handed that very seed back.
+    fn sample_checking_seed(
+        &self,
+        fuzzer: &Program<NamedDeBruijn>,
+    ) -> Result<Option<(Prng, PlutusData, bool)>, FuzzerError> {
+        Ok(self.draw(fuzzer)?.map(|(drawn, value)| {
+            let stalled = !drawn.choices().is_empty() && drawn.seed() == self.seed();
+            (self.reseed(drawn), value, stalled)
+        }))
+    }
+
+    /// Run a fuzzer with the given PRNG, as is.
+    fn draw(
+        &self,
+        fuzzer: &Program<NamedDeBruijn>,
+    ) -> Result<Option<(Prng, PlutusData)>, FuzzerError> {
+        let mut result = fuzzer.apply_data(self.uplc()).eval(ExBudget::max());
+        result
+            .result()
+            .and_then(Prng::from_result)
+            .map_err(|uplc_error| FuzzerError::Exited {
+                traces: result.logs(),
+                uplc_error,
+            })
+    }
+
+    /// Obtain a Prng back from a fuzzer execution. As a reminder, fuzzers have the following
+    /// signature:
+    ///
+    ///   type Fuzzer<a> = fn(Prng) -> Option<(Prng, a)>
+    ///
+    /// In nominal scenarios (i.e. when the fuzzer is made from a seed and evolve pseudo-randomly),
+    /// it cannot yield 'None'. When replayed however, we can't easily guarantee that the changes
+    /// made during shrinking aren't breaking underlying invariants (if only, because we run out of
+    /// values to replay). In such case, the replayed sequence is simply invalid and the fuzzer
+    /// aborted altogether with 'None'.
+    ///
+    /// Results that don't follow this encoding (e.g. because the fuzzer isn't one) are reported
+    /// as a deserialisation error, rather than aborting the whole run.
+    pub fn from_result(
+        result: Term<NamedDeBruijn>,
+    ) -> Result<Option<(Self, PlutusData)>, uplc::machine::Error> {
+        /// Interpret the given 'PlutusData' as one of two Prng constructors.
+        fn as_prng(cst: &PlutusData) -> Result<Prng, uplc::machine::Error> {
+            let seeded = match Prng::constr_fields(cst, Prng::SEEDED) {
+                Some(
+                    [PlutusData::BoundedBytes(bytes), PlutusData::BoundedBytes(choices), PlutusData::BigInt(size)],
+                ) => Some((
+                    bytes,
+                    choices,
+                    uplc::machine::value::from_pallas_bigint(size),
+                )),
+                Some([PlutusData::BoundedBytes(bytes), PlutusData::BoundedBytes(choices)]) => {
+                    Some((bytes, choices, 0.into()))
+                }
+                _ => None,
+            };
+
+            if let Some((bytes, choices, size)) = seeded {
+                return Ok(Prng::Seeded {
+                    choices: choices.to_vec(),
+                    uplc: Data::constr(
+                        Prng::SEEDED,
+                        vec![
+                            PlutusData::BoundedBytes(bytes.to_owned()),
+                            // Clear choices between seeded runs, to not
+                            // accumulate ALL choices ever made.
+                            PlutusData::BoundedBytes(vec![].into()),
+                            // Grow the size with each run.
+                            Data::integer(size + 1),
+                        ],
+                    ),
+                });
+            }
+
+            if let Some(
+                [PlutusData::BigInt(..), PlutusData::BoundedBytes(choices)]
+                | [PlutusData::BigInt(..), PlutusData::BoundedBytes(choices), PlutusData::BigInt(..)],
+            ) = Prng::constr_fields(cst, Prng::REPLAYED)
+            {
+                return Ok(Prng::Replayed {
+                    choices: choices.to_vec(),
+                    uplc: cst.clone(),
+                });
+            }
+
+            Err(Prng::malformed("PRNG", Prng::data_value(cst)))
+        }
+
+        let data = match &result {
+            Term::Constant(rc) => match rc.borrow() {
+                Constant::Data(data) => data,
+                _ => return Err(Prng::malformed("Option<(PRNG, a)>", Value::Con(rc.clone()))),
+            },
+            // Fuzzers are type-checked to return an 'Option', which is always data.
+            _ => unreachable!("Fuzzer yielded a malformed result? {result:#?}"),
+        };
+
+        if let Some([PlutusData::Array(elems)]) = Prng::constr_fields(data, Prng::SOME) {
+            if let [new_seed, value] = &elems[..] {
+                return Ok(Some((as_prng(new_seed)?, value.clone())));
+            }
+        }
+
+        // May occurs when replaying a fuzzer from a shrinked sequence of
+        // choices. If we run out of choices, or a choice end up being
+        // invalid as per the expectation, the fuzzer can't go further and
+        // fail.
+        if Prng::constr_fields(data, Prng::NONE).is_some() {
+            return Ok(None);
+        }
+
+        Err(Prng::malformed("Option<(PRNG, a)>", Prng::data_value(data)))
+    }
+
+    /// Fields of the given data, provided it is the constructor at index 'ix'. This hides away
+    /// how constructor indexes map onto Plutus data tags (e.g. 0 onto 121), which the prelude's
+    /// 'PRNG' and 'Option' both rely on.
+    fn constr_fields(data: &PlutusData, ix: u64) -> Option<&[PlutusData]> {
+        match data {
+            PlutusData::Constr(Constr { tag, fields, .. })
+                if uplc::machine::runtime::convert_tag_to_constr(*tag) == Some(ix) =>
+            {
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+
+    fn data_value(data: &PlutusData) -> Value {
+        Value::Con(Constant::Data(data.clone()).into())
+    }
+
+    fn malformed(expected: &str, value: Value) -> uplc::machine::Error {
+        uplc::machine::Error::DeserialisationError(expected.to_string(), value)
+    }
+}
+
+/// A compact view of a Prng's state, e.g. 'Seeded(seed=2a1f…, choices=[1, 2])', as opposed to
+/// its full on-chain representation.
+impl Display for Prng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Prng::Seeded { .. } => write!(
+                f,
+                "Seeded(seed={}, choices={:?})",
+                hex::encode(self.seed().unwrap_or_default()),
+                self.choices()
+            ),
+            Prng::Replayed { .. } => write!(f, "Replayed(choices={:?})", self.choices()),
+        }
+    }
+}
+
+/// ----- Counterexample -----------------------------------------------------------------
+///
+/// A counterexample is constructed from a test failure. It holds a value, and a sequence
+/// of random choices that led to this value. It holds a reference to the underlying
+/// property and fuzzer. In many cases, a counterexample can be simplified (a.k.a "shrinked")
+/// into a smaller counterexample.
+///
+/// Clones share the underlying property, and start from a copy of the choices already tried.
+#[derive(Clone)]
+pub struct Counterexample<'a> {
+    pub value: PlutusData,
+    /// The value as first found, before any simplification.
+    pub original: PlutusData,
+    pub choices: Vec<u8>,
+    pub cache: Cache<'a, PlutusData>,
+    /// Improvements accepted by each shrinking strategy, see 'ShrinkStats'.
+    pub stats: ShrinkStats,
+    /// Choices to shrink toward, see 'PropertyTest::shrink_target'.
+    pub target: Vec<u8>,
+    /// Number of candidate choices the fuzzer couldn't replay while shrinking (it yielded no
+    /// value, or failed). Many of those hint at a fuzzer whose invariants break easily.
+    pub invalid_replays: usize,
+    /// Every candidate considered while shrinking, in order, along with the verdict returned by
+    /// 'consider'. Only recorded when set to 'Some' beforehand, as large shrinks consider a lot
+    /// of candidates. Meant for diagnostics, e.g. to visualise how the shrinker went about it.
+    pub explored: Option<Vec<(Vec<u8>, bool)>>,
+    /// Chunk sizes tried by the chunked passes of 'simplify'.
+    pub schedule: ShrinkSchedule,
+    /// The size the value was drawn at (see 'Prng::size'); candidate choices are replayed at it.
+    pub size: usize,
+    strategy: ShrinkStrategy,
+}
+
+impl<'a> Counterexample<'a> {
+    /// Construct a counterexample for the given property, from a failing value and the choices
+    /// that led the property's fuzzer to it. The counterexample isn't simplified yet; see
+    /// 'simplify'.
+    pub fn new(property: &'a PropertyTest, value: PlutusData, choices: Vec<u8>) -> Self {
+        Counterexample::sized(property, value, choices, 0)
+    }
+
+    /// Same as 'new', for a value drawn at the given size (see 'Prng::size').
+    pub fn sized(
+        property: &'a PropertyTest,
+        value: PlutusData,
+        choices: Vec<u8>,
+        size: usize,
+    ) -> Self {
+        let mut counterexample = Counterexample::with_verdict(
+            &property.compiled.fuzzer,
+            value,
+            choices,
+            size,
+            |value| {
+                let is_failure = property.verdict(value);
+
+                let expect_failure = property.can_error;
+
+                // If the test no longer fails, it isn't better as we're only
+                // interested in counterexamples.
+                (expect_failure && !is_failure) || (!expect_failure && is_failure)
+            },
+        );
+
+        counterexample.target = property.shrink_target.clone();
+
+        counterexample
+    }
+
+    /// Same as 'sized', but for an arbitrary fuzzer and notion of counterexample: a value is one
+    /// whenever the given verdict holds on it.
+    pub fn with_verdict<F>(
+        fuzzer: &'a Program<NamedDeBruijn>,
+        value: PlutusData,
+        choices: Vec<u8>,
+        size: usize,
+        verdict: F,
+    ) -> Self
+    where
+        F: Fn(&PlutusData) -> bool + 'a,
+    {
+        Counterexample {
+            original: value.clone(),
+            value,
+            choices,
+            stats: ShrinkStats::default(),
+            target: Vec::new(),
+            invalid_replays: 0,
+            explored: None,
+            schedule: ShrinkSchedule::default(),
+            size,
+            strategy: ShrinkStrategy::Deletion,
+            cache: Cache::new(move |choices| {
+                match Prng::from_choices(choices).with_size(size).sample(fuzzer) {
+                    Err(..) => Status::Invalid,
+                    Ok(None) => Status::Invalid,
+                    Ok(Some((_, value))) if verdict(&value) => Status::Keep(value),
+                    Ok(Some(..)) => Status::Ignore,
+                }
+            }),
+        }
+    }
+
+    /// Replay the given choices through the fuzzer, and adopt them if they still lead to a
+    /// counterexample which is simpler than the current one (see 'is_simpler'). Returns whether
+    /// the choices are (now) the counterexample's.
+    pub fn consider(&mut self, choices: &[u8], progress: &mut dyn FnMut(ShrinkProgress)) -> bool {
+        let verdict = self.adopt(choices, progress);
+
+        if let Some(explored) = self.explored.as_mut() {
+            explored.push((choices.to_vec(), verdict));
+        }
+
+        verdict
+    }
+
+    fn adopt(&mut self, choices: &[u8], progress: &mut dyn FnMut(ShrinkProgress)) -> bool {
+        if choices == self.choices {
+            return true;
+        }
+
+        match self.cache.get(choices) {
+            Status::Invalid => {
+                self.invalid_replays += 1;
+                false
+            }
+            Status::Ignore => false,
+            Status::Keep(value) => {
+                // If these new choices are simpler, then we pick them as new choices and inform
+                // that it's been an improvement. Choices that move away from the target, if any,
+                // are never an improvement though.
+                if self.is_simpler(choices) && !self.moves_away_from_target(choices) {
+                    self.value = value;
+                    self.choices = choices.to_vec();
+                    self.stats.record(self.strategy);
+                    progress(ShrinkProgress {
+                        choices: self.choices.len(),
+                        summary: ShrinkProgress::summarize(&self.value),
+                    });
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Whether the given choices are simpler than the current ones: shorter, or as long but
+    /// closer to the target (choice by choice, from the first one), with ties broken by their
+    /// raw values. This is a strict total order: among candidates equally minimal otherwise, a
+    /// single one is ever adopted, however the shrinker comes across them.
+    fn is_simpler(&self, choices: &[u8]) -> bool {
+        let key = |choices: &[u8]| {
+            let distances = choices
+                .iter()
+                .enumerate()
+                .map(|(ix, choice)| self.target.get(ix).unwrap_or(&0).abs_diff(*choice))
+                .collect::<Vec<_>>();
+            (choices.len(), distances, choices.to_vec())
+        };
+
+        key(choices) < key(&self.choices)
+    }
+
+    /// Whether replacing the current choices with the given ones (of the same length) takes any
+    /// targeted choice further from its target. Deletions shift choices around, so they aren't
+    /// judged this way.
+    fn moves_away_from_target(&self, choices: &[u8]) -> bool {
+        choices.len() == self.choices.len()
+            && self
+                .target
+                .iter()
+                .zip(choices.iter().zip(self.choices.iter()))
+                .any(|(target, (new, old))| target.abs_diff(*new) > target.abs_diff(*old))
+    }
+
+    /// Size of the first chunks tried when deleting choices. Short sequences start from chunks
+    /// of 8, whereas longer ones start from (a power of two near) half their length, such that
+    /// large generated structures collapse in fewer passes. Chunk sizes are then halved down to
+    /// 1, so small deletions are still all tried.
+    fn max_deletion_chunk(len: usize) -> usize {
+        let half = len / 2;
+        if half <= 8 {
+            8
+        } else {
+            1 << (usize::BITS - 1 - half.leading_zeros())
+        }
+    }
+
+    /// Try to simplify a 'Counterexample' by manipulating the random sequence of generated values
+    /// (a.k.a. choices). While the implementation is quite involved, the strategy is rather simple
+    /// at least conceptually:
+    ///
+    /// Each time a (seeded) fuzzer generates a new value and a new seed, it also stores the
+    /// generated value in a vector, which we call 'choices'. If we re-run the test case with this
+    /// exact choice sequence, we end up with the exact same outcome.
+    ///
+    /// But, we can tweak chunks of this sequence in hope to generate a _smaller sequence_, thus
+    /// generally resulting in a _smaller counterexample_. Each transformations is applied on
+    /// chunks of size 8, 4, 2 and 1; until we no longer make progress (i.e. hit a fix point).
+    /// Deletions on long sequences start from larger chunks (see 'max_deletion_chunk'). Chunk
+    /// sizes of the deletion and zeroing passes can be tuned through 'schedule'.
+    ///
+    /// Before anything else, we try the empty sequence of choices which, when the fuzzer supports
+    /// it, regenerates its canonical simplest value (e.g. an empty list or zero). When that still
+    /// fails the property, there's nothing simpler to find.
+    ///
+    /// Then, as per MiniThesis, we consider the following transformations:
+    ///
+    /// - Deleting chunks
+    /// - Transforming chunks into sequence of zeroes
+    /// - Replacing chunks of values with smaller values
+    /// - Sorting chunks in ascending order
+    /// - Swapping nearby pairs
+    /// - Redistributing values between nearby pairs
+    ///
+    /// Properties may also declare a target (see 'PropertyTest::shrink_target'), toward which the
+    /// choices it covers are brought instead of zero.
+    ///
+    /// Since smaller choices are always preferred, fuzzers that draw a selector before drawing
+    /// from one of several branches (e.g. to bias a distribution) shrink toward their first
+    /// branch. Such fuzzers should hence list their simplest branch first.
+    pub fn simplify(&mut self) {
+        self.simplify_with(None, &mut |_| {})
+    }
+
+    /// Same as 'simplify', but reports each improvement to the given callback as it's found, and
+    /// gives up after the given timeout (if any), leaving the best counterexample found so far.
+    fn simplify_with(
+        &mut self,
+        timeout: Option<Duration>,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let expired = || deadline.map_or(false, |deadline| Instant::now() >= deadline);
+
+        self.strategy = ShrinkStrategy::Deletion;
+        if self.consider(&[], progress) {
+            return;
+        }
+
+        let mut prev;
+
+        loop {
+            if expired() {
+                return;
+            }
+
+            prev = self.choices.clone();
+
+            self.strategy = ShrinkStrategy::Deletion;
+
+            // First try deleting each choice we made in chunks. We try longer chunks because this
+            // allows us to delete whole composite elements: e.g. deleting an element from a
+            // generated list requires us to delete both the choice of whether to include it and
+            // also the element itself, which may involve more than one choice.
+            for k in self.schedule.deletion_chunks(self.choices.len()) {
+                let (mut i, mut underflow) = if self.choices.len() < k {
+                    (0, true)
+                } else {
+                    (self.choices.len() - k, false)
+                };
+
+                while !underflow {
+                    if expired() {
+                        return;
+                    }
+
+                    if i >= self.choices.len() {
+                        (i, underflow) = i.overflowing_sub(1);
+                        continue;
+                    }
+
+                    let j = i + k;
+
+                    let mut choices = [
+                        &self.choices[..i],
+                        if j < self.choices.len() {
+                            &self.choices[j..]
+                        } else {
+                            &[]
+                        },
+                    ]
+                    .concat();
+
+                    if !self.consider(&choices, progress) {
+                        // Perform an extra reduction step that decrease the size of choices near
+                        // the end, to cope with dependencies between choices, e.g. drawing a
+                        // number as a list length, and then drawing that many elements.
+                        //
+                        // This isn't perfect, but allows to make progresses in many cases.
+                        if i > 0 && choices[i - 1] > 0 {
+                            choices[i - 1] -= 1;
+                            // Like any successful deletion, this shifts the next choices to 'i';
+                            // which is therefore where to try again.
+                            if self.consider(&choices, progress) {
+                                continue;
+                            }
+                        }
+
+                        (i, underflow) = i.overflowing_sub(1);
+                    }
+                }
+            }
+
+            if !self.choices.is_empty() {
+                // Now we try replacing region of choices with zeroes. Note that unlike the above we
+                // skip k = 1 because we handle that in the next step. Often (but not always) a block
+                // of all zeroes is the smallest value that a region can be.
+                self.strategy = ShrinkStrategy::Zeroing;
+                for k in self.schedule.zeroing.clone() {
+                    let mut i = self.choices.len();
+                    while i >= k {
+                        if expired() {
+                            return;
+                        }
+
+                        let ivs = (i - k..i).map(|j| (j, 0)).collect::<Vec<_>>();
+                        i -= if self.replace(ivs, progress) { k } else { 1 }
+                    }
+                }
+
+                // Replace choices with smaller value, by doing a binary search. This will replace n
+                // with 0 or n - 1, if possible, but will also more efficiently replace it with, a
+                // smaller number than doing multiple subtractions would.
+                self.strategy = ShrinkStrategy::BinarySearch;
+                let (mut i, mut underflow) = (self.choices.len() - 1, false);
+                while !underflow {
+                    if expired() {
+                        return;
+                    }
+
+                    self.binary_search_replace(0, self.choices[i], |v| vec![(i, v)], progress);
+                    (i, underflow) = i.overflowing_sub(1);
+                }
+
+                // Likewise, bring targeted choices as close as possible to their target, by doing
+                // a binary search on their distance to it.
+                self.strategy = ShrinkStrategy::Targeting;
+                for i in 0..self.target.len().min(self.choices.len()) {
+                    if expired() {
+                        return;
+                    }
+
+                    let (target, current) = (self.target[i], self.choices[i]);
+                    self.binary_search_replace(
+                        0,
+                        target.abs_diff(current),
+                        |distance| {
+                            vec![(
+                                i,
+                                if current > target {
+                                    target + distance
+                                } else {
+                                    target - distance
+                                },
+                            )]
+                        },
+                        progress,
+                    );
+                }
+
+                // Sort out of orders chunks in ascending order
+                self.strategy = ShrinkStrategy::Sort;
+                let mut k = 8;
+                while k > 1 {
+                    let mut i = self.choices.len() - 1;
+                    while i >= k {
+                        let (from, to) = (i - k, i);
+                        self.replace(
+                            (from..to)
+                                .zip(self.choices[from..to].iter().cloned().sorted())
+                                .collect(),
+                            progress,
+                        );
+                        i -= 1;
+                    }
+                    k /= 2
+                }
+
+                // Try adjusting nearby pairs by:
+                //
+                // - Swapping them if they are out-of-order
+                // - Redistributing values between them.
+                for k in [2, 1] {
+                    let mut j = self.choices.len() - 1;
+                    while j >= k {
+                        let i = j - k;
+
+                        // Swap
+                        self.strategy = ShrinkStrategy::Swap;
+                        if self.choices[i] > self.choices[j] {
+                            self.replace(
+                                vec![(i, self.choices[j]), (j, self.choices[i])],
+                                progress,
+                            );
+                        }
+
+                        let iv = self.choices[i];
+                        let jv = self.choices[j];
+
+                        // Replace. Only so much can move to 'j' before it overflows; whatever
+                        // can't move stays in 'i'.
+                        self.strategy = ShrinkStrategy::Redistribution;
+                        let lo = iv.saturating_sub(u8::MAX - jv);
+                        if lo < iv {
+                            self.binary_search_replace(
+                                lo,
+                                iv,
+                                |v| vec![(i, v), (j, jv + (iv - v))],
+                                progress,
+                            );
+                        }
+
+                        j -= 1
+                    }
+                }
+            }
+
+            // If we've reached a fixed point, then we cannot shrink further. We've reached a
+            // (local) minimum, which is as good as a counterexample we'll get with this approach.
+            if prev.as_slice() == self.choices.as_slice() {
+                break;
+            }
+        }
+    }
+
+    /// Try to replace a value with a smaller value by doing a binary search between
+    /// two extremes. This converges relatively fast in order to shrink down values.
+    fn binary_search_replace<F>(
+        &mut self,
+        lo: u8,
+        hi: u8,
+        f: F,
+        progress: &mut dyn FnMut(ShrinkProgress),
+    ) -> u8
+    where
+        F: Fn(u8) -> Vec<(usize, u8)>,
+    {
+        if self.replace(f(lo), progress) {
+            return lo;
+        }
+
+        let mut lo = lo;
+        let mut hi = hi;
+
+        // Bounds may sit at the very edge of what a choice can be (e.g. 'lo = u8::MAX'), so
+        // arithmetic on them must not overflow.
+        while lo.saturating_add(1) < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.replace(f(mid), progress) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        hi
+    }
+
+    // Replace values in the choices vector, based on the index-value list provided
+    // and consider the resulting choices.
+    fn replace(&mut self, ivs: Vec<(usize, u8)>, progress: &mut dyn FnMut(ShrinkProgress)) -> bool {
+        let mut choices = self.choices.clone();
+
+        for (i, v) in ivs {
+            if i >= choices.len() {
+                return false;
+            }
+            choices[i] = v;
+        }
+
+        self.consider(&choices, progress)
+    }
+}
+
+/// Progress of a counterexample simplification, reported on each improvement. It only carries
+/// cheap information: the number of choices left, and a (shallow) summary of the current value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShrinkProgress {
+    pub choices: usize,
+    pub summary: String,
+}
+
+impl ShrinkProgress {
+    fn summarize(value: &PlutusData) -> String {
+        match value {
+            PlutusData::BigInt(i) => uplc::machine::value::from_pallas_bigint(i).to_string(),
+            PlutusData::BoundedBytes(bytes) => format!("bytes ({})", bytes.len()),
+            PlutusData::Array(items) => format!("list ({} items)", items.len()),
+            PlutusData::Map(entries) => format!("map ({} entries)", entries.len()),
+            PlutusData::Constr(Constr {
+                tag,
+                any_constructor,
+                fields,
+            }) => {
+                let ix = uplc::machine::runtime::convert_tag_to_constr(*tag)
+                    .or(*any_constructor)
+                    .unwrap_or_default();
+                format!("constr {ix} ({} fields)", fields.len())
+            }
+        }
+    }
+}
+
+/// ----- Differential -----------------------------------------------------------------
+///
+/// Differential testing checks that two programs (e.g. an optimised validator and a reference
+/// implementation) agree on every input drawn from a same fuzzer. Programs agree on an input when
+/// they both evaluate to the same term, or both fail. Execution costs and traces are not compared,
+/// as they're expected to differ between implementations.
+///
+/// Inputs on which the programs diverge are shrunk exactly like property counterexamples, within
+/// the same limits (see 'shrink_timeout' and 'max_choices').
+#[derive(Debug, Clone)]
+pub struct DifferentialTest {
+    pub left: Program<NamedDeBruijn>,
+    pub right: Program<NamedDeBruijn>,
+    pub fuzzer: Program<NamedDeBruijn>,
+    limits: Limits,
+}
+
+/// An input on which both programs of a 'DifferentialTest' disagree, with their outcomes.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub value: PlutusData,
+    pub choices: Vec<u8>,
+    pub left: Result<Term<NamedDeBruijn>, uplc::machine::Error>,
+    pub right: Result<Term<NamedDeBruijn>, uplc::machine::Error>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DifferentialResult {
+    /// The (shrunk) input on which programs first diverged, if any; or the error the fuzzer
+    /// exited with.
+    pub divergence: Result<Option<Divergence>, uplc::machine::Error>,
+    /// Number of inputs both programs were fed; fewer than asked when the fuzzer ran dry.
+    pub iterations: usize,
+    pub seed: u32,
+    /// Number of choices of an input that exceeded 'DifferentialTest::max_choices', which stopped
+    /// the run.
+    pub oversized_input: Option<usize>,
+}
+
+impl DifferentialTest {
+    pub fn new(
+        left: Program<NamedDeBruijn>,
+        right: Program<NamedDeBruijn>,
+        fuzzer: Program<NamedDeBruijn>,
+    ) -> Self {
+        DifferentialTest {
+            left,
+            right,
+            fuzzer,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Bound the time spent simplifying a divergence; see 'PropertyTestOptions::shrink_timeout'.
+    pub fn shrink_timeout(mut self, shrink_timeout: Duration) -> Self {
+        self.limits.shrink_timeout = Some(shrink_timeout);
+        self
+    }
+
+    /// Maximum number of choices a single input may be generated from; see
+    /// 'PropertyTestOptions::max_choices'.
+    pub fn max_choices(mut self, max_choices: usize) -> Self {
+        self.limits.max_choices = max_choices;
+        self
+    }
+
+    /// Feed both programs with at most `n` values drawn from a seed, stopping at the first one
+    /// they disagree on.
+    pub fn run(&self, seed: u32, n: usize) -> DifferentialResult {
+        let mut prng = Prng::from_seed(seed, 0);
+
+        let mut iterations = 0;
+
+        while iterations < n {
+            let (next_prng, value) = match prng.sample(&self.fuzzer) {
+                Ok(Some(sample)) => sample,
+                Ok(None) => break,
+                Err(FuzzerError::Oversized { choices }) => {
+                    return DifferentialResult {
+                        divergence: Ok(None),
+                        iterations,
+                        seed,
+                        oversized_input: Some(choices),
+                    }
+                }
+                Err(FuzzerError::Exited { uplc_error, .. }) => {
+                    return DifferentialResult {
+                        divergence: Err(uplc_error),
+                        iterations,
+                        seed,
+                        oversized_input: None,
+                    }
+                }
+            };
+
+            let choices = next_prng.choices();
+
+            if self.limits.check(choices.len()).is_err() {
+                return DifferentialResult {
+                    divergence: Ok(None),
+                    iterations,
+                    seed,
+                    oversized_input: Some(choices.len()),
+                };
+            }
+
+            iterations += 1;
+
+            if self.diverges(&value) {
+                let mut counterexample = Counterexample::with_verdict(
+                    &self.fuzzer,
+                    value,
+                    choices,
+                    prng.size(),
+                    |value| self.diverges(value),
+                );
+
+                counterexample.schedule = self.limits.shrink_schedule.clone();
+
+                if !counterexample.choices.is_empty() {
+                    counterexample.simplify_with(self.limits.shrink_timeout, &mut |_| {});
+                }
+
+                let (left, right) = self.outcomes(&counterexample.value);
+
+                return DifferentialResult {
+                    divergence: Ok(Some(Divergence {
+                        value: counterexample.value,
+                        choices: counterexample.choices,
+                        left,
+                        right,
+                    })),
+                    iterations,
+                    seed,
+                    oversized_input: None,
+                };
+            }
+
+            prng = next_prng;
+        }
+
+        DifferentialResult {
+            divergence: Ok(None),
+            iterations,
+            seed,
+            oversized_input: None,
+        }
+    }
+
+    fn outcomes(
+        &self,
+        value: &PlutusData,
+    ) -> (
+        Result<Term<NamedDeBruijn>, uplc::machine::Error>,
+        Result<Term<NamedDeBruijn>, uplc::machine::Error>,
+    ) {
+        (
+            PropertyTest::eval_program(&self.left, value).result(),
+            PropertyTest::eval_program(&self.right, value).result(),
+        )
+    }
+
+    fn diverges(&self, value: &PlutusData) -> bool {
+        match self.outcomes(value) {
+            (Ok(left), Ok(right)) => left != right,
+            (Err(..), Err(..)) => false,
+            (Ok(..), Err(..)) | (Err(..), Ok(..)) => true,
+        }
+    }
+}
+
+/// Number of improvements each shrinking strategy made to a counterexample (see
+/// 'Counterexample::simplify'). This helps telling apart the strategies that pay off from those
+/// that only burn evaluations on a given property. Deleting all choices at once (the very first
+/// attempt) counts as a deletion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShrinkStats {
+    pub deletion: usize,
+    pub zeroing: usize,
+    pub binary_search: usize,
+    pub sort: usize,
+    pub swap: usize,
+    pub redistribution: usize,
+    pub targeting: usize,
+}
+
+impl ShrinkStats {
+    pub fn total(&self) -> usize {
+        self.deletion
+            + self.zeroing
+            + self.binary_search
+            + self.sort
+            + self.swap
+            + self.redistribution
+            + self.targeting
+    }
+
+    fn record(&mut self, strategy: ShrinkStrategy) {
+        match strategy {
+            ShrinkStrategy::Deletion => self.deletion += 1,
+            ShrinkStrategy::Zeroing => self.zeroing += 1,
+            ShrinkStrategy::BinarySearch => self.binary_search += 1,
+            ShrinkStrategy::Sort => self.sort += 1,
+            ShrinkStrategy::Swap => self.swap += 1,
+            ShrinkStrategy::Redistribution => self.redistribution += 1,
+            ShrinkStrategy::Targeting => self.targeting += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShrinkStrategy {
+    Deletion,
+    Zeroing,
+    BinarySearch,
+    Sort,
+    Swap,
+    Redistribution,
+    Targeting,
+}
+
+/// ----- Ranking -----------------------------------------------------------------------
+///
+/// When several counterexamples are collected for a same property, showing all of them can be
+/// noisy. Counterexamples are therefore ranked by a weighted combination of:
+///
+/// - their size (i.e. the number of choices needed to generate them), smaller is better;
+/// - their execution cost, more expensive is more informative;
+/// - how rare their bucket (e.g. the labels they hit) is amongst all candidates.
+///
+#[derive(Debug, Clone)]
+pub struct Candidate<T> {
+    pub value: T,
+    pub size: usize,
+    pub spent_budget: ExBudget,
+    pub bucket: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Ranked<T> {
+    pub value: T,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankingWeights {
+    pub size: f64,
+    pub budget: f64,
+    pub diversity: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        RankingWeights {
+            size: 1.0,
+            budget: 1.0,
+            diversity: 1.0,
+        }
+    }
+}
+
+/// Rank counterexamples from the most to the least informative. Each criteria is normalized
+/// against the other candidates, so that weights can be compared with one another. Ties are
+/// resolved by keeping the original order.
+pub fn rank_counterexamples<T>(
+    examples: Vec<Candidate<T>>,
+    weights: RankingWeights,
+) -> Vec<Ranked<T>> {
+    let max_size = examples.iter().map(|c| c.size).max().unwrap_or(0).max(1) as f64;
+
+    let cost = |budget: &ExBudget| (budget.mem as f64) + (budget.cpu as f64);
+
+    let max_cost = examples
+        .iter()
+        .map(|c| cost(&c.spent_budget))
+        .fold(0.0, f64::max)
+        .max(1.0);
+
+    let mut buckets = BTreeMap::new();
+    for c in examples.iter() {
+        buckets
+            .entry(c.bucket.clone())
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+    }
+
+    examples
+        .into_iter()
+        .map(|c| {
+            let size = 1.0 - (c.size as f64) / max_size;
+            let budget = cost(&c.spent_budget) / max_cost;
+            let diversity = 1.0 / (buckets[&c.bucket] as f64);
+
+            Ranked {
+                score: weights.size * size
+                    + weights.budget * budget
+                    + weights.diversity * diversity,
+                value: c.value,
+            }
+        })
+        .sorted_by(|a, b| b.score.total_cmp(&a.score))
+        .collect()
+}
+
+/// ----- Cache -----------------------------------------------------------------------
+///
+/// A simple cache as a Patricia-trie to look for already explored options. The simplification
+/// steps does often generate the same paths and the generation of new test values as well as the
+/// properties can take a significant time.
+///
+/// Yet, sequences have interesting properties:
+///
+/// 1. The generation and test execution is entirely deterministic.
+///
+///
+pub struct Cache<'a, T> {
+    db: PatriciaMap<Status<T>>,
+    #[allow(clippy::type_complexity)]
+    run: Rc<dyn Fn(&[u8]) -> Status<T> + 'a>,
+}
+
+impl<'a, T: Clone> Clone for Cache<'a, T> {
+    fn clone(&self) -> Self {
+        Cache {
+            db: self.db.clone(),
+            run: self.run.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status<T> {
+    Keep(T),
+    Ignore,
+    Invalid,
+}
+
+impl<'a, T> Cache<'a, T>
+where
+    T: PartialEq + Clone,
+{
+    pub fn new<F>(run: F) -> Cache<'a, T>
+    where
+        F: Fn(&[u8]) -> Status<T> + 'a,
+    {
+        Cache {
+            db: PatriciaMap::new(),
+            run: Rc::new(run),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.db.len()
+    }
+
+    pub fn get(&mut self, choices: &[u8]) -> Status<T> {
+        if let Some((prefix, status)) = self.db.get_longest_common_prefix(choices) {
+            let status = status.clone();
+            if status != Status::Invalid || prefix == choices {
+                return status;
+            }
+        }
+
+        let status = self.run.deref()(choices);
+
+        // Clear longer path on non-invalid cases, as we will never reach them
+        // again due to a now-shorter prefix found.
+        //
+        // This hopefully keeps the cache under a reasonable size as we prune
+        // the tree as we discover shorter paths.
+        if status != Status::Invalid {
+            let keys = self
+                .db
+                .iter_prefix(choices)
+                .map(|(k, _)| k)
+                .collect::<Vec<_>>();
+            for k in keys {
+                self.db.remove(k);
+            }
+        }
+
+        self.db.insert(choices, status.clone());
+
+        status
+    }
+}
+
+// ----------------------------------------------------------------------------
+//
+// TestResult
+//
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum TestResult<U, T> {
+    UnitTestResult(UnitTestResult<U>),
+    PropertyTestResult(PropertyTestResult<T>),
+    /// A test that didn't run at all (e.g. because the suite timed out). Skipped tests are
+    /// neither successes nor failures; reports count them on their own.
+    Skipped(SkippedTest),
+}
+
+impl TestResult<(Constant, Rc<Type>), PlutusData> {
+    pub fn reify(
+        self,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+    ) -> TestResult<UntypedExpr, UntypedExpr> {
+        match self {
+            TestResult::UnitTestResult(test) => TestResult::UnitTestResult(test.reify(data_types)),
+            TestResult::PropertyTestResult(test) => {
+                TestResult::PropertyTestResult(test.reify(data_types))
+            }
+            TestResult::Skipped(test) => TestResult::Skipped(test),
+        }
+    }
+
+    /// Like 'reify', but leave property counterexamples as raw data (see
+    /// 'PropertyTestResult::reify_raw').
+    pub fn reify_raw(
+        self,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+    ) -> TestResult<UntypedExpr, UntypedExpr> {
+        match self {
+            TestResult::UnitTestResult(test) => TestResult::UnitTestResult(test.reify(data_types)),
+            TestResult::PropertyTestResult(test) => {
+                TestResult::PropertyTestResult(test.reify_raw())
+            }
+            TestResult::Skipped(test) => TestResult::Skipped(test),
+        }
+    }
+}
+
+impl<U, T> TestResult<U, T> {
+    /// The result of a run known to be that of a property, e.g. from 'PropertyTest::run'.
+    fn into_property(self) -> PropertyTestResult<T> {
+        match self {
+            TestResult::PropertyTestResult(result) => result,
+            TestResult::UnitTestResult(..) | TestResult::Skipped(..) => {
+                unreachable!("property returned unit-test result ?!")
+            }
+        }
+    }
+
+    /// Whether the test behaved as expected: either it passed, or it was expected to fail and
+    /// did fail (see 'is_expected_failure'). Skipped tests don't count as failures, hence are
+    /// successes in that sense; see 'is_skipped' to tell them apart.
+    pub fn is_success(&self) -> bool {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { success, .. }) => *success,
+            TestResult::Skipped(..) => true,
+            TestResult::PropertyTestResult(result)
+                if result.lacks_iterations()
+                    || result.nondeterministic
+                    || result.oversized_input.is_some() =>
+            {
+                false
+            }
+            TestResult::PropertyTestResult(PropertyTestResult {
+                counterexample: Err(..),
+                ..
+            }) => false,
+            TestResult::PropertyTestResult(PropertyTestResult {
+                counterexample: Ok(counterexample),
+                ..
+            }) => counterexample.is_some() == self.can_error(),
+        }
+    }
+
+    /// Whether the test did not behave as expected. This excludes errors that prevented the test
+    /// from running at all (see 'is_error').
+    pub fn is_failure(&self) -> bool {
+        !self.is_success() && !self.is_error()
+    }
+
+    /// Whether the test didn't run at all; see 'TestResult::Skipped'.
+    pub fn is_skipped(&self) -> bool {
+        matches!(self, TestResult::Skipped(..))
+    }
+
+    /// Whether the test could not run, because its fuzzer exited unexpectedly, or could not run
+    /// enough iterations (see 'PropertyTestResult::lacks_iterations'), or turned out
+    /// to be non-deterministic, or generated an oversized input.
+    pub fn is_error(&self) -> bool {
+        match self {
+            TestResult::UnitTestResult(..) | TestResult::Skipped(..) => false,
+            TestResult::PropertyTestResult(result) => {
+                result.counterexample.is_err()
+                    || result.lacks_iterations()
+                    || result.nondeterministic
+                    || result.oversized_input.is_some()
+            }
+        }
+    }
+
+    /// Whether the test was expected to fail ('fail' keyword) and did fail, which makes it a
+    /// success.
+    pub fn is_expected_failure(&self) -> bool {
+        self.can_error() && self.is_success()
+    }
+
+    pub fn can_error(&self) -> bool {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { test, .. }) => test.can_error,
+            TestResult::PropertyTestResult(PropertyTestResult { test, .. }) => test.can_error,
+            TestResult::Skipped(..) => false,
+        }
+    }
+
+    pub fn module(&self) -> &str {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { ref test, .. }) => test.module.as_str(),
+            TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
+                test.module.as_str()
+            }
+            TestResult::Skipped(SkippedTest { ref module, .. }) => module.as_str(),
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { ref test, .. }) => test.name.as_str(),
+            TestResult::PropertyTestResult(PropertyTestResult { ref test, .. }) => {
+                test.name.as_str()
+            }
+            TestResult::Skipped(SkippedTest { ref name, .. }) => name.as_str(),
+        }
+    }
+
+    /// Key by which results are canonically ordered: by module, then title, then kind (unit
+    /// tests before properties). See 'sort_results'.
+    pub fn sort_key(&self) -> (&str, &str, bool) {
+        (
+            self.module(),
+            self.title(),
+            matches!(self, TestResult::PropertyTestResult(..)),
+        )
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { duration, .. })
+            | TestResult::PropertyTestResult(PropertyTestResult { duration, .. }) => *duration,
+            TestResult::Skipped(..) => Duration::ZERO,
+        }
+    }
+
+    /// The assertion behind a unit test, if any. Property tests never carry assertions.
+    pub fn assertion(&self) -> Option<&Assertion<U>> {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { ref assertion, .. }) => assertion.as_ref(),
+            TestResult::PropertyTestResult(..) | TestResult::Skipped(..) => None,
+        }
+    }
+
+    pub fn traces(&self) -> &[String] {
+        match self {
+            TestResult::UnitTestResult(UnitTestResult { ref traces, .. })
+            | TestResult::PropertyTestResult(PropertyTestResult { ref traces, .. }) => {
+                traces.as_slice()
+            }
+            TestResult::Skipped(..) => &[],
+        }
+    }
+
+    /// Same as 'traces', but numbered in order of emission, and nested according to their
+    /// indentation (see 'Trace').
+    pub fn structured_traces(&self) -> Vec<Trace> {
+        self.traces()
+            .iter()
+            .enumerate()
+            .map(|(index, trace)| Trace::new(index, trace))
+            .collect()
+    }
+
+    /// Turn a (failed) result into an error. Failing properties carry the seed they ran from;
+    /// `suite_seed` is that of the suite they ran in, if any, from which they can be re-run.
+    pub fn into_error(&self, verbose: bool, suite_seed: Option<u32>) -> crate::Error {
+        let (name, module, path, src, seed, choices, invalid_replays) = match self {
+            TestResult::UnitTestResult(UnitTestResult { test, .. }) => (
+                test.name.to_string(),
+                test.module.to_string(),
+                test.input_path.to_path_buf(),
+                test.program.to_pretty(),
+                None,
+                Vec::new(),
+                0,
+            ),
+            TestResult::PropertyTestResult(PropertyTestResult {
+                test,
+                seed,
+                choices,
+                invalid_replays,
+                ..
+            }) => (
+                test.name.to_string(),
+                test.module.to_string(),
+                test.input_path.to_path_buf(),
+                test.program.to_pretty(),
+                *seed,
+                choices.clone(),
+                *invalid_replays,
+            ),
+            TestResult::Skipped(SkippedTest { name, module, .. }) => (
+                name.to_string(),
+                module.to_string(),
+                PathBuf::new(),
+                String::new(),
+                None,
+                Vec::new(),
+                0,
+            ),
+        };
+        crate::Error::TestFailure {
+            name,
+            module,
+            path,
+            src,
+            verbose,
+            seed,
+            suite_seed,
+            choices,
+            invalid_replays,
+        }
+    }
+}
+
+/// Sort results in their canonical order (see 'TestResult::sort_key'). Suites yield results in
+/// no particular order once run in parallel, or filtered; this gives reports comparable across
+/// runs.
+pub fn sort_results<U, T>(results: &mut [TestResult<U, T>]) {
+    results.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+}
+
+#[derive(Debug)]
+pub struct UnitTestResult<T> {
+    pub success: bool,
+    pub spent_budget: ExBudget,
+    pub traces: Vec<String>,
+    /// The term the test evaluated to, unless it errored.
+    pub output: Option<Term<NamedDeBruijn>>,
+    pub test: UnitTest,
+    pub assertion: Option<Assertion<T>>,
+    /// Field-level differences between both sides of a failing equality assertion, when both
+    /// sides are structured data. Only computed on reification.
+    pub diff: Vec<DataDiff>,
+    /// The (pretty-printed) output of a test that evaluated to something else than a boolean.
+    pub non_boolean: Option<String>,
+    /// The error raised while evaluating a test that wasn't expected to fail, if any; as opposed
+    /// to the test merely returning 'False'. For tests expected to fail, this is only set when
+    /// they failed for another reason than expected (see 'UnitTest::expected_error').
+    pub runtime_error: Option<String>,
+    /// The kind of error the test raised, if any; whether or not it was expected to fail. This
+    /// tells apart, say, a test failing on purpose from one running out of budget.
+    pub error_kind: Option<ErrorKind>,
+    /// The golden term of a test, and what it actually evaluated to, when those differ.
+    pub golden_mismatch: Option<GoldenMismatch>,
+    /// The traces a test declared, and those it actually emitted, when those differ.
+    pub logs_mismatch: Option<LogsMismatch>,
+    pub duration: Duration,
+}
+
+impl UnitTestResult<(Constant, Rc<Type>)> {
+    pub fn reify(
+        self,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+    ) -> UnitTestResult<UntypedExpr> {
+        let diff = match &self.assertion {
+            Some(Assertion {
+                bin_op: BinOp::Eq,
+                head: Ok((left, _)),
+                tail: Ok(tail),
+            }) if !self.success && tail.len() == 1 => {
+                match (constant_as_data(left), constant_as_data(&tail.first().0)) {
+                    (Some(left), Some(right)) => match diff_data(&left, &right) {
+                        // A difference at the root says nothing more than the assertion itself.
+                        diff if diff.iter().all(|d| d.path.is_empty()) => Vec::new(),
+                        diff => diff,
+                    },
+                    _ => Vec::new(),
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        UnitTestResult {
+            success: self.success,
+            spent_budget: self.spent_budget,
+            traces: self.traces,
+            output: self.output,
+            test: self.test,
+            duration: self.duration,
+            diff,
+            non_boolean: self.non_boolean,
+            runtime_error: self.runtime_error,
+            error_kind: self.error_kind,
+            golden_mismatch: self.golden_mismatch,
+            logs_mismatch: self.logs_mismatch,
+            assertion: self.assertion.and_then(|assertion| {
+                // No need to spend time/cpu on reifying assertions for successful
+                // tests since they aren't shown.
+                if self.success {
+                    return None;
+                }
+
+                Some(Assertion {
+                    bin_op: assertion.bin_op,
+                    head: assertion
+                        .head
+                        .map(|(cst, tipo)| reify_operand(data_types, cst, &tipo)),
+                    tail: assertion
+                        .tail
+                        .map(|xs| xs.mapped(|(cst, tipo)| reify_operand(data_types, cst, &tipo))),
+                })
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PropertyTestResult<T> {
+    pub test: PropertyTest,
+    pub counterexample: Result<Option<T>, uplc::machine::Error>,
+    /// All distinct counterexamples found, starting with 'counterexample'. There's at most one
+    /// unless the property was asked to collect more.
+    pub counterexamples: Vec<T>,
+    /// Choices that led to 'counterexample', if any. Replaying them through the fuzzer, at 'size',
+    /// yields the counterexample back.
+    pub choices: Vec<u8>,
+    /// The size 'counterexample' was drawn at (see 'Prng::size').
+    pub size: usize,
+    pub iterations: usize,
+    /// The (1-based) iteration at which the property first failed, before shrinking. This hints
+    /// at how rare the failure is.
+    pub failed_at: Option<usize>,
+    /// Number of iterations required for the property to pass. Only smoke runs require any (see
+    /// 'PropertyTest::smoke'): every iteration counts otherwise, as fuzzers can't discard inputs.
+    pub min_successful: usize,
+    /// Choices drawn at each iteration, in order. Only recorded on demand.
+    pub trail: Vec<Vec<u8>>,
+    pub labels: BTreeMap<String, usize>,
+    pub traces: Vec<String>,
+    /// The seed the property ran from, as given to 'PropertyTest::run'. Within a suite, this is
+    /// derived from the suite's own seed (see 'RunSeed::test_seed'). None when the property
+    /// didn't run from any single seed, e.g. smoke runs, or sweeps that didn't fail.
+    pub seed: Option<u32>,
+    pub duration: Duration,
+    /// Whether the counterexample leads to a different outcome once traces are erased from the
+    /// property. See 'PropertyTest::eval_untraced'.
+    pub untraced_divergence: bool,
+    /// Whether replaying the choices of the counterexample led to another value, or outcome.
+    /// Only checked on demand (see 'PropertyTestOptions::check_determinism').
+    pub nondeterministic: bool,
+    /// Whether the fuzzer draws choices without advancing its seed. Iterations remain
+    /// independent since seeds are derived by the framework (see 'Prng::sample'), but values
+    /// drawn within a same iteration are all alike. This is told by the first value sampled in
+    /// parallel (see 'PropertyTest::run_n_times'); runs that sample none, e.g. those collecting
+    /// several counterexamples, or failing on their corpus, never tell.
+    pub stalled_fuzzer: bool,
+    /// The error raised by the property on the counterexample, when it failed by erroring rather
+    /// than by returning 'False'; e.g. an exhausted budget or a failing builtin.
+    pub eval_error: Option<uplc::machine::Error>,
+    /// Improvements made by each shrinking strategy while simplifying 'counterexample'.
+    pub shrink_stats: ShrinkStats,
+    /// Number of choices of an input that exceeded 'PropertyTestOptions::max_choices', which
+    /// stopped the run.
+    pub oversized_input: Option<usize>,
+    /// The label of the first check that doesn't hold for the counterexample, when the property
+    /// returns a labelled conjunction (see 'conjunction').
+    pub failed_check: Option<String>,
+    /// See 'Counterexample::invalid_replays'.
+    pub invalid_replays: usize,
+    /// The property fully applied to its counterexample, if any; i.e. the exact program that
+    /// fails. Meant to be dumped and fed to external UPLC tools (see 'PropertyTest::apply').
+    pub failing_program: Option<Program<NamedDeBruijn>>,
+    /// Traces emitted by the fuzzer while generating the counterexample, if any; see
+    /// 'PropertyTest::scenario'.
+    pub scenario: Vec<String>,
+    /// The number of iterations the property was meant to run, when it ran fewer to fit within
+    /// the suite timeout; see 'Test::run_in_suite'.
+    pub reduced_from: Option<usize>,
+}
+
+/// A trace emitted by a test, along with its position amongst all traces of that test. Traces
+/// carry no structure on their own; their nesting is therefore derived from their indentation:
+/// each leading tab, or pair of leading spaces, is one level deeper. This lets tests calling a
+/// same helper many times group what the helper traces, with e.g. `trace @"  inner"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub index: usize,
+    pub depth: usize,
+    pub message: String,
+}
+
+impl Trace {
+    fn new(index: usize, trace: &str) -> Self {
+        let message = trace.trim_start_matches([' ', '\t']);
+
+        let depth = trace[..trace.len() - message.len()]
+            .chars()
+            .map(|c| if c == '\t' { 2 } else { 1 })
+            .sum::<usize>()
+            / 2;
+
+        Trace {
+            index,
+            depth,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl<T> PropertyTestResult<T> {
+    /// The result of a property that found nothing, in no time: no counterexample, no iteration,
+    /// nothing to warn about. Results are built from it by struct update, so that only what sets
+    /// them apart needs spelling out.
+    pub fn empty(test: PropertyTest, seed: Option<u32>) -> Self {
+        PropertyTestResult {
+            test,
+            counterexample: Ok(None),
+            counterexamples: Vec::new(),
+            choices: Vec::new(),
+            size: 0,
+            iterations: 0,
+            failed_at: None,
+            min_successful: 0,
+            trail: Vec::new(),
+            labels: BTreeMap::new(),
+            traces: Vec::new(),
+            seed,
+            duration: Duration::ZERO,
+            untraced_divergence: false,
+            nondeterministic: false,
+            stalled_fuzzer: false,
+            eval_error: None,
+            shrink_stats: ShrinkStats::default(),
+            oversized_input: None,
+            failed_check: None,
+            invalid_replays: 0,
+            failing_program: None,
+            scenario: Vec::new(),
+            reduced_from: None,
+        }
+    }
+
+    /// Whether the property found no counterexample, but without going through enough iterations
+    /// to be trusted; i.e. a smoke run that never got to evaluate the property.
+    pub fn lacks_iterations(&self) -> bool {
+        matches!(self.counterexample, Ok(None)) && self.iterations < self.min_successful
+    }
+
+    /// Whether shrinking made any progress on the counterexample. When it didn't, the value
+    /// originally found is already as simple as the shrinker can make it.
+    pub fn was_shrunk(&self) -> bool {
+        self.shrink_stats.total() > 0
+    }
+
+    /// The type of values the property was fuzzed over, pretty-printed (e.g. 'List<Int>').
+    pub fn fuzzed_type(&self) -> String {
+        self.test.fuzzer.type_info.to_pretty(0)
+    }
+
+    /// What the counterexample, if any, stands for. For properties expected to fail, it isn't a
+    /// bug but the input that rightfully triggered the failure.
+    pub fn finding(&self) -> Option<Finding> {
+        match self.counterexample {
+            Ok(Some(..)) if self.test.can_error => Some(Finding::ExpectedFailure),
+            Ok(Some(..)) => Some(Finding::Counterexample),
+            Ok(None) | Err(..) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finding {
+    /// An input falsifying a property meant to hold.
+    Counterexample,
+    /// An input triggering the failure of a property meant to fail.
+    ExpectedFailure,
+}
+
+impl PropertyTestResult<PlutusData> {
+    /// Reify counterexamples as Aiken expressions of the fuzzer's type. Should that fail, the
+    /// counterexamples are shown as raw data instead; reporting them is better than nothing.
+    pub fn reify(
+        self,
+        data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+    ) -> PropertyTestResult<UntypedExpr> {
+        let tipo = self.test.fuzzer.type_info.clone();
+
+        self.reify_with(|counterexample: PlutusData| {
+            UntypedExpr::reify_data(data_types, counterexample.clone(), &tipo)
+                .unwrap_or_else(|_| UntypedExpr::reify_blind(counterexample))
+        })
+    }
+
+    /// Show counterexamples as raw data, without converting them to values of the fuzzer's type.
+    /// This is much cheaper for large types, when only the verdict matters. Counterexamples can
+    /// still be reified later on, from their 'choices' (see 'Prng::from_choices').
+    pub fn reify_raw(self) -> PropertyTestResult<UntypedExpr> {
+        self.reify_with(UntypedExpr::reify_blind)
+    }
+
+    fn reify_with(
+        self,
+        reify: impl Fn(PlutusData) -> UntypedExpr,
+    ) -> PropertyTestResult<UntypedExpr> {
+        PropertyTestResult {
+            counterexample: self.counterexample.map(|ok| ok.map(&reify)),
+            counterexamples: self.counterexamples.into_iter().map(&reify).collect(),
+            choices: self.choices,
+            size: self.size,
+            iterations: self.iterations,
+            failed_at: self.failed_at,
+            min_successful: self.min_successful,
+            trail: self.trail,
+            test: self.test,
+            labels: self.labels,
+            traces: self.traces,
+            seed: self.seed,
+            duration: self.duration,
+            untraced_divergence: self.untraced_divergence,
+            nondeterministic: self.nondeterministic,
+            stalled_fuzzer: self.stalled_fuzzer,
+            eval_error: self.eval_error,
+            shrink_stats: self.shrink_stats,
+            oversized_input: self.oversized_input,
+            failed_check: self.failed_check,
+            invalid_replays: self.invalid_replays,
+            failing_program: self.failing_program,
+            scenario: self.scenario,
+            reduced_from: self.reduced_from,
+        }
+    }
+}
+
+impl PropertyTestResult<UntypedExpr> {
+    /// Whether two reified counterexamples are the same for reporting purposes; that is, whether
+    /// they read the same once formatted. Distinct data may reify alike (e.g. when shown as raw
+    /// data or through an opaque type), and alike values may still differ in their locations.
+    pub fn same_counterexample(left: &UntypedExpr, right: &UntypedExpr) -> bool {
+        let pretty = |expr| Formatter::new().expr(expr, false).to_pretty_string(80);
+        left == right || pretty(left) == pretty(right)
+    }
+
+    /// Counterexamples that read differently from all those preceding them (see
+    /// 'same_counterexample'), starting with 'counterexample'.
+    pub fn distinct_counterexamples(&self) -> Vec<&UntypedExpr> {
+        let first = self.counterexample.as_ref().ok().and_then(Option::as_ref);
+
+        first.into_iter().chain(self.counterexamples.iter()).fold(
+            Vec::new(),
+            |mut distinct, counterexample| {
+                if !distinct
+                    .iter()
+                    .any(|known| Self::same_counterexample(known, counterexample))
+                {
+                    distinct.push(counterexample);
+                }
+                distinct
+            },
+        )
+    }
+
+    /// The source of a unit test applying the property to its counterexample, if any; meant to be
+    /// pasted next to the property, to keep the failing case covered once fixed. Properties are
+    /// plain functions of their fuzzed value, so the unit test simply calls it. For properties
+    /// expected to fail, so is the unit test.
+    ///
+    /// Note that counterexamples reified as raw data (see 'reify_raw') won't type-check as such.
+    pub fn regression_test(&self) -> Option<String> {
+        let counterexample = match &self.counterexample {
+            Ok(Some(counterexample)) => counterexample,
+            _ => return None,
+        };
+
+        let value = Formatter::new()
+            .expr(counterexample, false)
+            .to_pretty_string(70)
+            .lines()
+            .collect::<Vec<_>>()
+            .join("\n  ");
+
+        Some(format!(
+            "test {name}_counterexample(){fail} {{\n  {name}({value})\n}}\n",
+            name = self.test.name,
+            fail = if self.test.can_error { " fail" } else { "" },
+        ))
+    }
+}
+
+impl PropertyTestResult<PlutusData> {
+    /// Capture this failure as a self-contained bundle, which can be shared (e.g. attached to a
+    /// bug report) and later replayed with 'PropertyTest::load_bundle'. There's no bundle for
+    /// properties that didn't yield any counterexample.
+    pub fn bundle(&self) -> Option<Bundle> {
+        let counterexample = match &self.counterexample {
+            Ok(Some(counterexample)) => counterexample,
+            _ => return None,
+        };
+
+        Some(Bundle {
+            version: Bundle::VERSION,
+            module: self.test.module.clone(),
+            name: self.test.name.clone(),
+            seed: self.seed,
+            choices: hex::encode(&self.choices),
+            size: self.size,
+            counterexample: hex::encode(
+                uplc::plutus_data_to_bytes(counterexample)
+                    .expect("Failed to serialise counterexample?"),
+            ),
+        })
+    }
+}
+
+/// ----- Bundle ---------------------------------------------------------------
+///
+/// A minimal, reproducible failure of a property test. It identifies the test and captures the
+/// seed it ran from, the (shrunk) choices that led to the counterexample, and the counterexample
+/// itself as hex-encoded CBOR.
+///
+/// Bundles are serialised as JSON. The schema is versioned; any change to it must come with a
+/// new VERSION. Older versions remain loadable:
+///
+/// - version 1 bundles predate sizes (see 'Prng::size'); their choices are replayed at size 0.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    pub module: String,
+    pub name: String,
+    #[serde(default)]
+    pub seed: Option<u32>,
+    pub choices: String,
+    #[serde(default)]
+    pub size: usize,
+    pub counterexample: String,
+}
+
+impl Bundle {
+    pub const VERSION: u32 = 2;
+}
+
+#[derive(Debug, Clone, thiserror::Error, miette::Diagnostic)]
+pub enum BundleError {
+    #[error(
+        "Unsupported bundle version {version}; expected at most {}",
+        Bundle::VERSION
+    )]
+    UnsupportedVersion { version: u32 },
+
+    #[error("Bundle was made for {found}, not {expected}")]
+    TestMismatch { expected: String, found: String },
+
+    #[error("Malformed bundle: invalid '{field}'")]
+    Malformed { field: &'static str },
+
+    #[error("Replaying the bundle's choices no longer yields its counterexample")]
+    Diverged,
+}
+
+impl<T> UnitTestResult<T> {
+    /// Capture the observable output of this test, to compare it against a stored snapshot.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            version: Snapshot::VERSION,
+            module: self.test.module.clone(),
+            name: self.test.name.clone(),
+            output: self.output.as_ref().map(|term| term.to_pretty()),
+            traces: self.traces.clone(),
+        }
+    }
+}
+
+/// ----- Summary ---------------------------------------------------------------
+///
+/// Aggregates over an entire test run, meant for tooling such as dashboards. The total budget
+/// only accounts for unit tests, as property tests run many times over.
+///
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RunSummary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errored: usize,
+    pub expected_failures: usize,
+    pub skipped: usize,
+    pub iterations: usize,
+    /// Iterations run by property tests alone.
+    pub property_iterations: usize,
+    /// Improvements made while shrinking counterexamples, across all properties.
+    pub shrink_steps: usize,
+    pub spent_budget: ExBudget,
+    pub duration_ms: u64,
+    /// Time spent in unit tests and in property tests respectively, summed over tests. Tests run
+    /// in parallel, so this tells where the effort goes rather than how long the run took.
+    pub unit_duration_ms: u64,
+    pub property_duration_ms: u64,
+}
+
+impl RunSummary {
+    /// Fold the results of a run, given the (wall-clock) duration of the whole run. This isn't
+    /// the sum of each test's duration, since tests run in parallel.
+    pub fn new<U, T>(results: &[TestResult<U, T>], duration: Duration) -> Self {
+        // Durations are summed as is, and only then truncated to milliseconds; lest short tests
+        // count for nothing.
+        let (summary, unit_duration, property_duration) = results.iter().fold(
+            (
+                RunSummary {
+                    total: 0,
+                    passed: 0,
+                    failed: 0,
+                    errored: 0,
+                    expected_failures: 0,
+                    skipped: 0,
+                    iterations: 0,
+                    property_iterations: 0,
+                    shrink_steps: 0,
+                    spent_budget: ExBudget { mem: 0, cpu: 0 },
+                    duration_ms: duration.as_millis() as u64,
+                    unit_duration_ms: 0,
+                    property_duration_ms: 0,
+                },
+                Duration::ZERO,
+                Duration::ZERO,
+            ),
+            |(mut summary, mut unit_duration, mut property_duration), result| {
+                summary.total += 1;
+
+                if result.is_skipped() {
+                    summary.skipped += 1;
+                } else if result.is_expected_failure() {
+                    summary.expected_failures += 1;
+                } else if result.is_success() {
+                    summary.passed += 1;
+                } else if result.is_error() {
+                    summary.errored += 1;
+                } else {
+                    summary.failed += 1;
+                }
+
+                match result {
+                    TestResult::UnitTestResult(UnitTestResult {
+                        spent_budget,
+                        duration,
+                        ..
+                    }) => {
+                        summary.iterations += 1;
+                        summary.spent_budget.mem += spent_budget.mem;
+                        summary.spent_budget.cpu += spent_budget.cpu;
+                        unit_duration += *duration;
+                    }
+                    TestResult::PropertyTestResult(PropertyTestResult {
+                        iterations,
+                        shrink_stats,
+                        duration,
+                        ..
+                    }) => {
+                        summary.iterations += iterations;
+                        summary.property_iterations += iterations;
+                        summary.shrink_steps += shrink_stats.total();
+                        property_duration += *duration;
+                    }
+                    TestResult::Skipped(..) => {}
+                }
+
+                (summary, unit_duration, property_duration)
+            },
+        );
+
+        RunSummary {
+            unit_duration_ms: unit_duration.as_millis() as u64,
+            property_duration_ms: property_duration.as_millis() as u64,
+            ..summary
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialise run summary?")
+    }
+}
+
+/// Check that no test spent more than a given budget, reporting every test that did. This is a
+/// guardrail against code whose cost balloons, on top of whatever each test asserts.
+///
+/// Property tests are out of scope for now: they don't record the budget any of their iterations
+/// spent, so there's nothing to hold against the ceiling and they are always let through. Only
+/// unit tests are checked.
+pub fn check_budget_ceiling<U, T>(
+    results: &[TestResult<U, T>],
+    ceiling: ExBudget,
+) -> Result<(), BudgetCeilingError> {
+    let offenders = results
+        .iter()
+        .filter_map(|result| match result {
+            TestResult::UnitTestResult(UnitTestResult {
+                test, spent_budget, ..
+            }) if spent_budget.mem > ceiling.mem || spent_budget.cpu > ceiling.cpu => {
+                Some(BudgetOverrun {
+                    module: test.module.clone(),
+                    name: test.name.clone(),
+                    spent_budget: *spent_budget,
+                })
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if offenders.is_empty() {
+        Ok(())
+    } else {
+        Err(BudgetCeilingError { ceiling, offenders })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetOverrun {
+    pub module: String,
+    pub name: String,
+    pub spent_budget: ExBudget,
+}
+
+#[derive(Debug, Clone, thiserror::Error, miette::Diagnostic)]
+#[error(
+    "{} test(s) exceeded the budget ceiling (mem: {}, cpu: {}):{}",
+    .offenders.len(),
+    .ceiling.mem,
+    .ceiling.cpu,
+    .offenders
+        .iter()
+        .map(|overrun| format!(
+            "\n  {}.{} (mem: {}, cpu: {})",
+            overrun.module, overrun.name, overrun.spent_budget.mem, overrun.spent_budget.cpu
+        ))
+        .collect::<String>()
+)]
+pub struct BudgetCeilingError {
+    pub ceiling: ExBudget,
+    pub offenders: Vec<BudgetOverrun>,
+}
+
+/// Check that every unit test asserts something, reporting those that merely return a constant
+/// 'True'. Such tests pass trivially, which is more often a mistake than an intent, so this is
+/// meant for a strict mode, on top of whatever each test asserts.
+pub fn check_strict<U, T>(results: &[TestResult<U, T>]) -> Result<(), StrictModeError> {
+    let offenders = results
+        .iter()
+        .filter_map(|result| match result {
+            TestResult::UnitTestResult(UnitTestResult { test, .. }) if test.trivial => {
+                Some(IneffectiveTest {
+                    module: test.module.clone(),
+                    name: test.name.clone(),
+                })
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if offenders.is_empty() {
+        Ok(())
+    } else {
+        Err(StrictModeError { offenders })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IneffectiveTest {
+    pub module: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error, miette::Diagnostic)]
+#[error(
+    "{} test(s) have no effective assertion:{}",
+    .offenders.len(),
+    .offenders
+        .iter()
+        .map(|test| format!("\n  {}.{}", test.module, test.name))
+        .collect::<String>()
+)]
+pub struct StrictModeError {
+    pub offenders: Vec<IneffectiveTest>,
+}
+
+/// How often a property fails across independent seeds; see
+/// 'PropertyTest::estimate_failure_rate'. Seeds on which the fuzzer itself errored are counted
+/// apart, and count neither as failures nor as successes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureStats {
+    pub seeds: usize,
+    pub failures: usize,
+    pub errors: usize,
+    pub iterations: usize,
+    /// A few distinct (shrunk) counterexamples, along with the seed that led to each.
+    pub examples: Vec<(u32, PlutusData)>,
+}
+
+/// A seed for which the shrinker ended up further than recorded; see
+/// 'PropertyTest::check_shrink_regressions'. No choices means no counterexample was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShrinkRegression {
+    pub seed: u32,
+    pub expected: Vec<u8>,
+    pub actual: Option<Vec<u8>>,
+}
+
+impl FailureStats {
+    const MAX_EXAMPLES: usize = 3;
+
+    /// Proportion of seeds, amongst those that ran to completion, yielding a counterexample.
+    pub fn failure_rate(&self) -> f64 {
+        let completed = self.seeds - self.errors;
+        if completed == 0 {
+            0.0
+        } else {
+            self.failures as f64 / completed as f64
+        }
+    }
+}
+
+/// ----- Flakiness -------------------------------------------------------------
+///
+/// Running a same suite twice (e.g. from a same seed) should yield the same outcomes. Tests whose
+/// outcome differs between two runs are flaky: either their fuzzer isn't deterministic, or they
+/// sit on some boundary (e.g. an execution budget) which a run crosses and not the other.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlakyTest<T> {
+    pub module: String,
+    pub name: String,
+    /// Whether the test succeeded in the first and second run, respectively.
+    pub success: (bool, bool),
+    /// The counterexample found in the first and second run, respectively. Always 'None' for
+    /// unit tests.
+    pub counterexample: (Option<T>, Option<T>),
+}
+
+/// Compare the results of two runs of a same suite, matching tests by module and title, and
+/// report those whose success or counterexample differs. Tests only present in one of the runs,
+/// or skipped in either, can't be compared, and are left out.
+pub fn flaky_tests<U, T: PartialEq + Clone>(
+    first: &[TestResult<U, T>],
+    second: &[TestResult<U, T>],
+) -> Vec<FlakyTest<T>> {
+    fn counterexample<U, T: Clone>(result: &TestResult<U, T>) -> Option<T> {
+        match result {
+            TestResult::PropertyTestResult(PropertyTestResult {
+                counterexample: Ok(counterexample),
+                ..
+            }) => counterexample.clone(),
+            TestResult::PropertyTestResult(..)
+            | TestResult::UnitTestResult(..)
+            | TestResult::Skipped(..) => None,
+        }
+    }
+
+    let second = second
+        .iter()
+        .map(|result| ((result.module(), result.title()), result))
+        .collect::<BTreeMap<_, _>>();
+
+    first
+        .iter()
+        .filter_map(|before| {
+            let after = second.get(&(before.module(), before.title()))?;
+
+            if before.is_skipped() || after.is_skipped() {
+                return None;
+            }
+
+            let success = (before.is_success(), after.is_success());
+            let counterexample = (counterexample(before), counterexample(after));
+
+            if success.0 == success.1 && counterexample.0 == counterexample.1 {
+                return None;
+            }
+
+            Some(FlakyTest {
+                module: before.module().to_string(),
+                name: before.title().to_string(),
+                success,
+                counterexample,
+            })
+        })
+        .collect()
+}
+
+/// ----- Snapshot ---------------------------------------------------------------
+///
+/// The observable output of a unit test: the term it evaluates to, and the traces it emits along
+/// the way. Snapshots are meant to be committed as 'golden' files, so that any drift in a test's
+/// output is noticed; even when the test itself still passes.
+///
+/// Snapshots are serialised as JSON. The schema is versioned; any change to it must come with a
+/// new VERSION.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub module: String,
+    pub name: String,
+    pub output: Option<String>,
+    pub traces: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// The output matches the stored snapshot.
+    Matched,
+    /// There was no stored snapshot yet, so one was written.
+    Created,
+    /// The output drifted from the stored snapshot, which was overwritten.
+    Updated { previous: Snapshot },
+    /// The output drifted from the stored snapshot.
+    Drifted { expected: Snapshot },
+}
+
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum SnapshotError {
+    #[error("I couldn't access snapshot {}: {error}", path.display())]
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Malformed snapshot {}: {error}", path.display())]
+    Malformed {
+        path: PathBuf,
+        error: serde_json::Error,
+    },
+}
+
+impl Snapshot {
+    pub const VERSION: u32 = 1;
+
+    /// Location of a test's snapshot within a snapshot directory.
+    pub fn path(dir: &Path, module: &str, name: &str) -> PathBuf {
+        dir.join(format!("{}.{name}.snap.json", module.replace('/', ".")))
+    }
+
+    /// Compare against the snapshot stored in the given directory. Missing snapshots are always
+    /// written, whereas drifting ones are only overwritten when asked to `update`.
+    pub fn verify(&self, dir: &Path, update: bool) -> Result<SnapshotOutcome, SnapshotError> {
+        let path = Snapshot::path(dir, &self.module, &self.name);
+
+        let io_error = |error| SnapshotError::Io {
+            path: path.clone(),
+            error,
+        };
+
+        let previous = match fs::read_to_string(&path) {
+            Ok(json) => Some(serde_json::from_str::<Snapshot>(&json).map_err(|error| {
+                SnapshotError::Malformed {
+                    path: path.clone(),
+                    error,
+                }
+            })?),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(io_error(error)),
+        };
+
+        let outcome = match previous {
+            Some(previous) if &previous == self => return Ok(SnapshotOutcome::Matched),
+            Some(expected) if !update => return Ok(SnapshotOutcome::Drifted { expected }),
+            Some(previous) => SnapshotOutcome::Updated { previous },
+            None => SnapshotOutcome::Created,
+        };
+
+        fs::create_dir_all(dir).map_err(io_error)?;
+
+        let json = serde_json::to_string_pretty(self).expect("Failed to serialise snapshot?");
+
+        fs::write(&path, json).map_err(io_error)?;
+
+        Ok(outcome)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Assertion<T> {
+    pub bin_op: BinOp,
+    pub head: Result<T, ()>,
+    pub tail: Result<Vec1<T>, ()>,
+}
+
+impl<T> Assertion<T> {
+    /// The left-most operand and the remaining ones, provided they could all be evaluated.
+    pub fn operands(&self) -> Option<(&T, &Vec1<T>)> {
+        match (&self.head, &self.tail) {
+            (Ok(head), Ok(tail)) => Some((head, tail)),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<TypedExpr> for Assertion<TypedExpr> {
+    type Error = ();
+
+    fn try_from(body: TypedExpr) -> Result<Self, Self::Error> {
+        match body {
+            TypedExpr::BinOp {
+                name,
+                tipo,
+                left,
+                right,
+                ..
+            } if tipo == bool() => {
+                // 'and' and 'or' are left-associative operators.
+                match (*right).clone().try_into() {
+                    Ok(Assertion {
+                        bin_op,
+                        head: Ok(head),
+                        tail: Ok(tail),
+                        ..
+                    }) if bin_op == name => {
+                        let mut both = vec1![head];
+                        both.extend(tail);
+                        Ok(Assertion {
+                            bin_op: name,
+                            head: Ok(*left),
+                            tail: Ok(both),
+                        })
+                    }
+                    _ => Ok(Assertion {
+                        bin_op: name,
+                        head: Ok(*left),
+                        tail: Ok(vec1![*right]),
+                    }),
+                }
+            }
+
+            // NOTE drill through trace-if-false operators for better errors.
+            TypedExpr::If {
+                branches,
+                final_else,
+                ..
+            } => {
+                if let [IfBranch {
+                    condition, body, ..
+                }] = &branches[..]
+                {
+                    let then_is_true = match body {
+                        TypedExpr::Var {
+                            name, constructor, ..
+                        } => name == "True" && constructor.tipo == bool(),
+                        _ => false,
+                    };
+
+                    let else_is_wrapped_false = match *final_else {
+                        TypedExpr::Trace { then, .. } => match *then {
+                            TypedExpr::Var {
+                                name, constructor, ..
+                            } => name == "False" && constructor.tipo == bool(),
+                            _ => false,
+                        },
+                        _ => false,
+                    };
+
+                    if then_is_true && else_is_wrapped_false {
+                        return condition.to_owned().try_into();
+                    }
+                }
+
+                Err(())
+            }
+
+            TypedExpr::Trace { then, .. } => (*then).try_into(),
+            TypedExpr::Emit { then, .. } => (*then).try_into(),
+
+            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                if let Ok(Assertion {
+                    bin_op,
+                    head: Ok(head),
+                    tail: Ok(tail),
+                }) = expressions.last().unwrap().to_owned().try_into()
+                {
+                    let replace = |expr| {
+                        let mut expressions = expressions.clone();
+                        expressions.pop();
+                        expressions.push(expr);
+                        TypedExpr::Sequence {
+                            expressions,
+                            location: Span::empty(),
+                        }
+                    };
+
+                    Ok(Assertion {
+                        bin_op,
+                        head: Ok(replace(head)),
+                        tail: Ok(tail.mapped(replace)),
+                    })
+                } else {
+                    Err(())
+                }
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+impl Assertion<UntypedExpr> {
+    #[allow(clippy::just_underscores_and_digits)]
+    pub fn to_string(&self, stream: Stream, expect_failure: bool) -> String {
+        let red = |s: &str| {
+            format!("× {s}")
+                .if_supports_color(stream, |s| s.red())
+                .if_supports_color(stream, |s| s.bold())
+                .to_string()
+        };
+
+        if self.head.is_err() {
+            return red("program failed");
+        }
+
+        fn fmt_side(side: &UntypedExpr, stream: Stream) -> String {
+            let __ = "│".if_supports_color(stream, |s| s.red());
+
+            Formatter::new()
+                .expr(side, false)
+                .to_pretty_string(60)
+                .lines()
+                .map(|line| format!("{__} {line}"))
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+
+        let left = fmt_side(self.head.as_ref().unwrap(), stream);
+
+        let tail = self.tail.as_ref().unwrap();
+
+        let right = fmt_side(tail.first(), stream);
+
+        // A failing inequality, or an equality expected to fail, has both sides evaluating to the
+        // same value. Showing it twice would only obscure what was expected in the first place.
+        let expects_difference = if expect_failure {
+            self.bin_op == BinOp::Eq
+        } else {
+            self.bin_op == BinOp::NotEq
+        };
+
+        if expects_difference && left == right {
+            return [
+                red("expected these not to be equal, but both evaluated to"),
+                left,
+            ]
+            .join("\n");
+        }
+
+        format!(
+            "{}{}{}",
+            red("expected"),
+            if expect_failure && self.bin_op == BinOp::Or {
+                " neither\n"
+                    .if_supports_color(stream, |s| s.red())
+                    .if_supports_color(stream, |s| s.bold())
+                    .to_string()
+            } else {
+                "\n".to_string()
+            },
+            if expect_failure {
+                match self.bin_op {
+                    BinOp::And => [
+                        left,
+                        red("and"),
+                        [
+                            tail.mapped_ref(|s| fmt_side(s, stream))
+                                .join(format!("\n{}\n", red("and")).as_str()),
+                            if tail.len() > 1 {
+                                red("to not all be true")
+                            } else {
+                                red("to not both be true")
+                            },
+                        ]
+                        .join("\n"),
+                    ],
+                    BinOp::Or => [
+                        left,
+                        red("nor"),
+                        [
+                            tail.mapped_ref(|s| fmt_side(s, stream))
+                                .join(format!("\n{}\n", red("nor")).as_str()),
+                            red("to be true"),
+                        ]
+                        .join("\n"),
+                    ],
+                    BinOp::Eq => [left, red("to not equal"), right],
+                    BinOp::NotEq => [left, red("to not be different"), right],
+                    BinOp::LtInt => [left, red("to not be lower than"), right],
+                    BinOp::LtEqInt => [left, red("to not be lower than or equal to"), right],
+                    BinOp::GtInt => [left, red("to not be greater than"), right],
+                    BinOp::GtEqInt => [left, red("to not be greater than or equal to"), right],
+                    _ => unreachable!("unexpected non-boolean binary operator in assertion?"),
+                }
+                .join("\n")
+            } else {
+                match self.bin_op {
+                    BinOp::And => [
+                        left,
+                        red("and"),
+                        [
+                            tail.mapped_ref(|s| fmt_side(s, stream))
+                                .join(format!("\n{}\n", red("and")).as_str()),
+                            if tail.len() > 1 {
+                                red("to all be true")
+                            } else {
+                                red("to both be true")
+                            },
+                        ]
+                        .join("\n"),
+                    ],
+                    BinOp::Or => [
+                        left,
+                        red("or"),
+                        [
+                            tail.mapped_ref(|s| fmt_side(s, stream))
+                                .join(format!("\n{}\n", red("or")).as_str()),
+                            red("to be true"),
+                        ]
+                        .join("\n"),
+                    ],
+                    BinOp::Eq => [left, red("to equal"), right],
+                    BinOp::NotEq => [left, red("to not equal"), right],
+                    BinOp::LtInt => [left, red("to be lower than"), right],
+                    BinOp::LtEqInt => [left, red("to be lower than or equal to"), right],
+                    BinOp::GtInt => [left, red("to be greater than"), right],
+                    BinOp::GtEqInt => [left, red("to be greater than or equal to"), right],
+                    _ => unreachable!("unexpected non-boolean binary operator in assertion?"),
+                }
+                .join("\n")
+            }
+        )
+    }
+}
+
+/// ----- Data diff ------------------------------------------------------------
+///
+/// Structural differences between two Plutus data, to point at what differs in otherwise large
+/// values. Each difference is located by a path from the root of both values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataPathSegment {
+    Field(usize),
+    Index(usize),
+    Entry(usize),
+}
+
+impl Display for DataPathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataPathSegment::Field(ix) => write!(f, "field {ix}"),
+            DataPathSegment::Index(ix) => write!(f, "index {ix}"),
+            DataPathSegment::Entry(ix) => write!(f, "entry {ix}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataDiff {
+    pub path: Vec<DataPathSegment>,
+    pub left: PlutusData,
+    pub right: PlutusData,
+}
+
+impl Display for DataDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "at {}: ", self.path.iter().join(", "))?;
+        }
+
+        write!(
+            f,
+            "expected {}, got {}",
+            fmt_data(&self.right),
+            fmt_data(&self.left)
+        )
+    }
+}
+
+fn fmt_data(data: &PlutusData) -> String {
+    match data {
+        PlutusData::BigInt(i) => uplc::machine::value::from_pallas_bigint(i).to_string(),
+        PlutusData::BoundedBytes(bytes) => format!("#\"{}\"", hex::encode(bytes.as_slice())),
+        _ => Constant::Data(data.clone()).to_pretty(),
+    }
+}
+
+/// Compute the differences between two Plutus data. Constructors with the same tag and arity, as
+/// well as lists and maps of the same length, are compared element-wise. Anything else that isn't
+/// equal is a difference on its own.
+pub fn diff_data(left: &PlutusData, right: &PlutusData) -> Vec<DataDiff> {
+    let mut diffs = Vec::new();
+    diff_data_at(&mut Vec::new(), left, right, &mut diffs);
+    diffs
+}
+
+fn diff_data_at(
+    path: &mut Vec<DataPathSegment>,
+    left: &PlutusData,
+    right: &PlutusData,
+    diffs: &mut Vec<DataDiff>,
+) {
+    if left == right {
+        return;
+    }
+
+    let mut nested = |segment, left, right, diffs: &mut Vec<DataDiff>| {
+        path.push(segment);
+        diff_data_at(path, left, right, diffs);
+        path.pop();
+    };
+
+    match (left, right) {
+        (PlutusData::Constr(l), PlutusData::Constr(r))
+            if l.tag == r.tag
+                && l.any_constructor == r.any_constructor
+                && l.fields.len() == r.fields.len() =>
+        {
+            for (ix, (l, r)) in l.fields.iter().zip(r.fields.iter()).enumerate() {
+                nested(DataPathSegment::Field(ix), l, r, diffs);
+            }
+        }
+        (PlutusData::Array(l), PlutusData::Array(r)) if l.len() == r.len() => {
+            for (ix, (l, r)) in l.iter().zip(r.iter()).enumerate() {
+                nested(DataPathSegment::Index(ix), l, r, diffs);
+            }
+        }
+        (PlutusData::Map(l), PlutusData::Map(r)) if l.len() == r.len() => {
+            for (ix, ((lk, lv), (rk, rv))) in l.iter().zip(r.iter()).enumerate() {
+                if lk == rk {
+                    nested(DataPathSegment::Entry(ix), lv, rv, diffs);
+                } else {
+                    nested(DataPathSegment::Entry(ix), lk, rk, diffs);
+                }
+            }
+        }
+        _ => diffs.push(DataDiff {
+            path: path.clone(),
+            left: left.clone(),
+            right: right.clone(),
+        }),
+    }
+}
+
+/// Reify an assertion operand as an Aiken expression of the given type. Should that fail (e.g.
+/// because the type isn't amongst the known data types), the operand is shown as raw data
+/// instead; or as a raw UPLC constant as a last resort. Reporting a failure matters more than
+/// how pretty it is.
+fn reify_operand(
+    data_types: &IndexMap<&DataTypeKey, &TypedDataType>,
+    cst: Constant,
+    tipo: &Type,
+) -> UntypedExpr {
+    UntypedExpr::reify_constant(data_types, cst.clone(), tipo).unwrap_or_else(|_| {
+        match constant_as_data(&cst) {
+            Some(data) => UntypedExpr::reify_blind(data),
+            None => UntypedExpr::Var {
+                location: Span::empty(),
+                name: cst.to_pretty(),
+            },
+        }
+    })
+}
+
+/// View a constant as Plutus data, when it has a straightforward representation as such.
+fn constant_as_data(cst: &Constant) -> Option<PlutusData> {
+    match cst {
+        Constant::Data(data) => Some(data.clone()),
+        Constant::Integer(i) => Some(Data::integer(i.clone())),
+        Constant::ByteString(bytes) => Some(Data::bytestring(bytes.clone())),
+        Constant::ProtoList(_, items) => items
+            .iter()
+            .map(constant_as_data)
+            .collect::<Option<Vec<_>>>()
+            .map(Data::list),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        module::{CheckedModule, CheckedModules},
+        utils,
+    };
+    use aiken_lang::{
+        ast::{CallArg, Definition, ModuleKind, TraceLevel, Tracing, UnOp},
+        builtins,
+        format::Formatter,
+        line_numbers::LineNumbers,
+        parser,
+        parser::{extra::ModuleExtra, token::Base},
+        IdGenerator,
+    };
+    use indoc::indoc;
+    use std::collections::HashMap;
+
+    const TEST_KIND: ModuleKind = ModuleKind::Lib;
+
+    impl Test {
+        pub fn from_source(src: &str) -> (Self, IndexMap<DataTypeKey, TypedDataType>) {
+            Self::from_sources(&[], src)
+        }
+
+        /// Like 'from_source', but with extra (named) modules that the test module may import.
+        /// Dependencies are checked in order, so they can only import preceding ones.
+        pub fn from_sources(
+            dependencies: &[(&str, &str)],
+            src: &str,
+        ) -> (Self, IndexMap<DataTypeKey, TypedDataType>) {
+            Self::from_sources_with(dependencies, src, |_| {})
+        }
+
+        /// Like 'from_sources', but lets the caller alter the test once type-checked; e.g. to
+        /// make up a test the type-checker wouldn't let through.
+        pub fn from_sources_with(
+            dependencies: &[(&str, &str)],
+            src: &str,
+            alter: impl FnOnce(&mut TypedTest),
+        ) -> (Self, IndexMap<DataTypeKey, TypedDataType>) {
+            let id_gen = IdGenerator::new();
+
+            let module_name = "";
+
+            let mut module_types = HashMap::new();
+            module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
+            module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+
+            let mut functions = builtins::prelude_functions(&id_gen);
+            let mut data_types = builtins::prelude_data_types(&id_gen);
+            let mut module_sources = HashMap::new();
+            let mut modules = CheckedModules::default();
+
+            let mut warnings = vec![];
+
+            for (name, dependency) in dependencies {
+                let (mut ast, _) =
+                    parser::module(dependency, TEST_KIND).expect("Failed to parse dependency");
+                ast.name = name.to_string();
+                let ast = ast
+                    .infer(
+                        &id_gen,
+                        TEST_KIND,
+                        module_name,
+                        &module_types,
+                        Tracing::All(TraceLevel::Verbose),
+                        &mut warnings,
+                    )
+                    .expect("Failed to type-check dependency.");
+
+                module_types.insert(name.to_string(), ast.type_info.clone());
+
+                ast.register_definitions(&mut functions, &mut data_types);
+
+                module_sources.insert(
+                    name.to_string(),
+                    (dependency.to_string(), LineNumbers::new(dependency)),
+                );
+
+                modules.insert(
+                    name.to_string(),
+                    CheckedModule {
+                        kind: TEST_KIND,
+                        extra: ModuleExtra::default(),
+                        name: name.to_string(),
+                        code: dependency.to_string(),
+                        ast,
+                        package: String::new(),
+                        input_path: PathBuf::new(),
+                    },
+                );
+            }
+
+            let (ast, extra) = parser::module(src, TEST_KIND).expect("Failed to parse module");
+            let ast = ast
+                .infer(
+                    &id_gen,
+                    TEST_KIND,
+                    module_name,
+                    &module_types,
+                    Tracing::All(TraceLevel::Verbose),
+                    &mut warnings,
+                )
+                .expect("Failed to type-check module.");
+
+            module_types.insert(module_name.to_string(), ast.type_info.clone());
+
+            let mut module = CheckedModule {
+                kind: TEST_KIND,
+                extra,
+                name: module_name.to_string(),
+                code: src.to_string(),
+                ast,
+                package: String::new(),
+                input_path: PathBuf::new(),
+            };
+
+            module.attach_doc_and_module_comments();
+
+            let mut test = module
+                .ast
+                .definitions()
+                .filter_map(|def| match def {
+                    Definition::Test(test) => Some(test.clone()),
+                    _ => None,
+                })
+                .last()
+                .expect("No test found in declared src?");
+
+            alter(&mut test);
+
+            module
+                .ast
+                .register_definitions(&mut functions, &mut data_types);
+
+            module_sources.insert(
+                module_name.to_string(),
+                (src.to_string(), LineNumbers::new(src)),
+            );
+
+            modules.insert(module_name.to_string(), module);
+
+            let mut generator = CodeGenerator::new(
+                utils::indexmap::as_ref_values(&functions),
+                utils::indexmap::as_ref_values(&data_types),
+                utils::indexmap::as_str_ref_values(&module_types),
+                utils::indexmap::as_str_ref_values(&module_sources),
+                Tracing::All(TraceLevel::Verbose),
+            );
+
+            (
+                Self::from_function_definition(
+                    &mut generator,
+                    test.to_owned(),
+                    module_name.to_string(),
+                    PathBuf::new(),
+                ),
+                data_types,
+            )
+        }
+    }
+
+    const PRELUDE: &str = indoc! { r#"
+        use aiken/builtin
+
+        const max_int: Int = 255
+
+        pub fn int() -> Fuzzer<Int> {
+          fn(prng: PRNG) -> Option<(PRNG, Int)> {
+            when prng is {
+              Seeded { seed, choices, size } -> {
+                 let choice =
+                   seed
+                     |> builtin.index_bytearray(0)
+
+                 Some((
+                   Seeded {
+                     seed: builtin.blake2b_256(seed),
+                     choices: builtin.cons_bytearray(choice, choices),
+                     size,
+                   },
+                   choice
+                 ))
+              }
+
+              Replayed { cursor, choices, size } -> {
+                if cursor >= 1 {
+                    let cursor = cursor - 1
+                    Some((
+                      Replayed { choices, cursor, size },
+                      builtin.index_bytearray(choices, cursor)
+                    ))
+                } else {
+                    None
+                }
+              }
+            }
+          }
+        }
+
+        fn bool() -> Fuzzer<Bool> {
+          int() |> map(fn(n) { n % 2 == 0 })
+        }
+
+        fn bytearray() -> Fuzzer<ByteArray> {
+          int()
+            |> map(
+                 fn(n) {
+                   n
+                     |> builtin.integer_to_bytearray(True, 32, _)
+                     |> builtin.blake2b_256()
+                     |> builtin.slice_bytearray(8, 4, _)
+                 },
+               )
+        }
+
+        pub fn constant(a: a) -> Fuzzer<a> {
+          fn(s0) { Some((s0, a)) }
+        }
+
+        pub fn and_then(fuzz_a: Fuzzer<a>, f: fn(a) -> Fuzzer<b>) -> Fuzzer<b> {
+          fn(s0) {
+            when fuzz_a(s0) is {
+              Some((s1, a)) -> f(a)(s1)
+              None -> None
+            }
+          }
+        }
+
+        pub fn map(fuzz_a: Fuzzer<a>, f: fn(a) -> b) -> Fuzzer<b> {
+          fn(s0) {
+            when fuzz_a(s0) is {
+              Some((s1, a)) -> Some((s1, f(a)))
+              None -> None
+            }
+          }
+        }
+
+        pub fn map2(fuzz_a: Fuzzer<a>, fuzz_b: Fuzzer<b>, f: fn(a, b) -> c) -> Fuzzer<c> {
+          fn(s0) {
+            when fuzz_a(s0) is {
+              Some((s1, a)) ->
+                when fuzz_b(s1) is {
+                  Some((s2, b)) -> Some((s2, f(a, b)))
+                  None -> None
+                }
+              None -> None
+            }
+          }
+        }
+    "#};
+
+    fn property(src: &str) -> (PropertyTest, impl Fn(PlutusData) -> String) {
+        let src = format!("{PRELUDE}\n{src}");
+
+        match Test::from_source(&src) {
+            (Test::PropertyTest(test), data_types) => {
+                let type_info = test.fuzzer.type_info.clone();
+
+                let reify = move |counterexample| {
+                    let data_type_refs = utils::indexmap::as_ref_values(&data_types);
+                    let expr = UntypedExpr::reify_data(&data_type_refs, counterexample, &type_info)
+                        .expect("Failed to reify value.");
+                    Formatter::new().expr(&expr, false).to_pretty_string(70)
+                };
+
+                (test, reify)
+            }
+            (Test::UnitTest(..), _) => {
+                panic!("Expected to yield a PropertyTest but found a UnitTest")
+            }
+        }
+    }
+
+    fn unit(src: &str) -> UnitTest {
+        unit_with_types(src).0
+    }
+
+    fn unit_with_types(src: &str) -> (UnitTest, IndexMap<DataTypeKey, TypedDataType>) {
+        match Test::from_source(src) {
+            (Test::UnitTest(test), data_types) => (test, data_types),
+            (Test::PropertyTest(..), _) => {
+                panic!("Expected to yield a UnitTest but found a PropertyTest")
+            }
+        }
+    }
+
+    impl<U, T> TestResult<U, T> {
+        fn as_unit(&self) -> &UnitTestResult<U> {
+            match self {
+                TestResult::UnitTestResult(result) => result,
+                TestResult::PropertyTestResult(..) | TestResult::Skipped(..) => {
+                    unreachable!("unit test returned property-test result ?!")
+                }
+            }
+        }
+
+        fn into_unit(self) -> UnitTestResult<U> {
+            match self {
+                TestResult::UnitTestResult(result) => result,
+                TestResult::PropertyTestResult(..) | TestResult::Skipped(..) => {
+                    unreachable!("unit test returned property-test result ?!")
+                }
+            }
+        }
+    }
+
+    impl PropertyTest {
+        fn expect_failure(&self) -> Counterexample {
+            let mut labels = BTreeMap::new();
+            let mut remaining = PropertyTest::DEFAULT_MAX_SUCCESS;
+            let limits = &Limits::default();
+            match self.run_n_times(
+                &mut remaining,
+                42,
+                &mut labels,
+                &mut false,
+                limits,
+                &mut |_| {},
+            ) {
+                Ok(Some(counterexample)) => counterexample,
+                _ => panic!("expected property to fail but it didn't."),
+            }
+        }
+    }
+
+    #[test]
+    fn test_prop_basic() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        assert!(prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .is_success());
+    }
+
+    #[test]
+    fn test_prop_imported_fuzzer() {
+        let qualified = indoc! { r#"
+            use shared/fuzz
+
+            test foo(n: Int via fuzz.int()) {
+                n < 42
+            }
+        "#};
+
+        let unqualified = indoc! { r#"
+            use shared/fuzz.{int}
+
+            test foo(n: Int via int()) {
+                n < 42
+            }
+        "#};
+
+        for src in [qualified, unqualified] {
+            let (prop, data_types) = match Test::from_sources(&[("shared/fuzz", PRELUDE)], src) {
+                (Test::PropertyTest(test), data_types) => (test, data_types),
+                (Test::UnitTest(..), _) => {
+                    panic!("Expected to yield a PropertyTest but found a UnitTest")
+                }
+            };
+
+            let counterexample = prop.expect_failure();
+
+            let data_type_refs = utils::indexmap::as_ref_values(&data_types);
+            let expr = UntypedExpr::reify_data(
+                &data_type_refs,
+                counterexample.value,
+                &prop.fuzzer.type_info,
+            )
+            .expect("Failed to reify value.");
+
+            assert_eq!(
+                Formatter::new().expr(&expr, false).to_pretty_string(70),
+                "42"
+            );
+        }
+    }
+
+    #[test]
+    fn test_prop_labels() {
+        let (prop, _) = property(indoc! { r#"
+            fn label(str: String) -> Void {
+              str
+                |> builtin.append_string(@"\0", _)
+                |> builtin.debug(Void)
+            }
+
+            test foo(head_or_tail via bool()) {
+                if head_or_tail {
+                    label(@"head")
+                } else {
+                    label(@"tail")
+                }
+                True
+            }
+        "#});
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert!(
+            result
+                .labels
+                .iter()
+                .eq(vec![(&"head".to_string(), &49), (&"tail".to_string(), &51)]),
+            "labels: {:#?}",
+            result.labels
+        )
+    }
+
+    #[test]
+    fn test_prop_stop_condition() {
+        let (prop, _) = property(indoc! { r#"
+            fn label(str: String) -> Void {
+              str
+                |> builtin.append_string(@"\0", _)
+                |> builtin.debug(Void)
+            }
+
+            test foo(n: Int via int()) {
+                label(@"any")
+                n < 100
+            }
+        "#});
+
+        let run = |stop_condition| {
+            prop.clone()
+                .run_with::<()>(PropertyTestOptions::new(42).stop_condition(stop_condition))
+                .into_property()
+        };
+
+        let first_failure = run(StopCondition::FirstFailure);
+        let run_all = run(StopCondition::RunAll);
+
+        assert!(first_failure.iterations < PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert_eq!(run_all.iterations, PropertyTest::DEFAULT_MAX_SUCCESS);
+
+        assert_eq!(
+            run_all.labels.get("any"),
+            Some(&PropertyTest::DEFAULT_MAX_SUCCESS)
+        );
+
+        assert_eq!(run_all.failed_at, first_failure.failed_at);
+        assert!(!run_all.choices.is_empty());
+        assert_eq!(run_all.choices, first_failure.choices);
+    }
+
+    #[test]
+    fn test_prop_regression_test() {
+        let src = format!(
+            "{PRELUDE}\n{}",
+            indoc! { r#"
+                test foo(n: Int via int()) {
+                    n < 100
+                }
+            "#}
+        );
+
+        let (prop, data_types) = match Test::from_source(&src) {
+            (Test::PropertyTest(test), data_types) => (test, data_types),
+            (Test::UnitTest(..), _) => {
+                panic!("Expected to yield a PropertyTest but found a UnitTest")
+            }
+        };
+
+        let regression_test = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property()
+            .reify(&utils::indexmap::as_ref_values(&data_types))
+            .regression_test()
+            .expect("property should have failed");
+
+        assert_eq!(
+            regression_test,
+            indoc! { r#"
+                test foo_counterexample() {
+                  foo(100)
+                }
+            "#}
+        );
+
+        // The regression test fails just like the property did.
+        assert!(!unit(&format!("{src}\n{regression_test}"))
+            .run::<PlutusData>()
+            .is_success());
+    }
+
+    #[test]
+    fn test_fuzzer_generate() {
+        let (prop, data_types) = match Test::from_source(&format!(
+            "{PRELUDE}\n{}",
+            indoc! { r#"
+                test foo(n: Int via int()) {
+                    n >= 0
+                }
+            "#}
+        )) {
+            (Test::PropertyTest(test), data_types) => (test, data_types),
+            (Test::UnitTest(..), _) => {
+                panic!("Expected to yield a PropertyTest but found a UnitTest")
+            }
+        };
+
+        let data_types = utils::indexmap::as_ref_values(&data_types);
+
+        let generate = |seed| {
+            prop.fuzzer
+                .generate(seed, &data_types)
+                .map(|(next_seed, value)| {
+                    (
+                        next_seed,
+                        Formatter::new().expr(&value, false).to_pretty_string(70),
+                    )
+                })
+                .expect("fuzzer should yield a value")
+        };
+
+        let (next_seed, value) = generate(42);
+
+        assert_eq!(generate(42), (next_seed, value.clone()));
+        assert_ne!(next_seed, 42);
+        assert!(value.parse::<u8>().is_ok(), "{value}");
+
+        // Chaining seeds yields a sequence of (varied) values.
+        let values = (0..10)
+            .scan(42, |seed, _| {
+                let (next_seed, value) = generate(*seed);
+                *seed = next_seed;
+                Some(value)
+            })
+            .collect::<std::collections::HashSet<_>>();
+        assert!(values.len() > 1, "{values:?}");
+    }
+
+    #[test]
+    fn test_prop_sweep() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        let result = prop.run_sweep::<()>(0..3).into_property();
+
+        assert!(matches!(result.counterexample, Ok(None)));
+        assert_eq!(result.iterations, 3 * PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert_eq!(result.seed, None);
+
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 255
+            }
+        "#});
+
+        let failing_seed = (0..20)
+            .find(|seed| {
+                !prop
+                    .clone()
+                    .run::<()>(*seed, PropertyTest::DEFAULT_MAX_SUCCESS)
+                    .is_success()
+            })
+            .expect("no failing seed in sweep range");
+
+        let result = prop.run_sweep::<()>(0..20).into_property();
+
+        assert!(matches!(result.counterexample, Ok(Some(..))));
+        assert_eq!(result.seed, Some(failing_seed));
+    }
+
+    #[test]
+    fn test_prop_many_counterexamples() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 50 || n >= 60 && n < 200
+            }
+        "#});
+
+        let result = prop
+            .clone()
+            .run_with::<()>(
+                PropertyTestOptions::new(42)
+                    .max_success(PropertyTest::DEFAULT_MAX_SUCCESS)
+                    .max_counterexamples(5),
+            )
+            .into_property();
+
+        assert_eq!(result.counterexamples.len(), 2);
+        assert_ne!(result.counterexamples[0], result.counterexamples[1]);
+        assert!(
+            matches!(result.counterexample, Ok(Some(ref first)) if first == &result.counterexamples[0])
+        );
+        assert_eq!(result.iterations, PropertyTest::DEFAULT_MAX_SUCCESS);
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert_eq!(result.counterexamples.len(), 1);
+    }
+
+    #[test]
+    fn test_distinct_counterexamples() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 50 || n >= 60 && n < 200
+            }
+        "#});
+
+        let mut result = prop
+            .run_with::<()>(
+                PropertyTestOptions::new(42)
+                    .max_success(PropertyTest::DEFAULT_MAX_SUCCESS)
+                    .max_counterexamples(5),
+            )
+            .into_property()
+            .reify(&IndexMap::new());
+
+        assert_eq!(result.distinct_counterexamples().len(), 2);
+
+        let again = result.counterexamples[0].clone();
+        result.counterexamples.push(again);
+        assert_eq!(result.counterexamples.len(), 3);
+        assert_eq!(result.distinct_counterexamples().len(), 2);
+
+        assert!(PropertyTestResult::same_counterexample(
+            &UntypedExpr::UInt {
+                location: Span::empty(),
+                value: "42".to_string(),
+                base: Base::Decimal {
+                    numeric_underscore: false
+                },
+            },
+            &UntypedExpr::UInt {
+                location: Span::create(14, 2),
+                value: "42".to_string(),
+                base: Base::Decimal {
+                    numeric_underscore: false
+                },
+            },
+        ));
+    }
+
+    #[test]
+    fn test_prop_smoke() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 42
+            }
+        "#});
+
+        let result = prop.smoke::<()>().into_property();
+
+        assert!(matches!(result.counterexample, Ok(None)));
+        assert_eq!(result.iterations, 1);
+
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n > 0
+            }
+        "#});
+
+        let result = prop.smoke::<()>().into_property();
+
+        assert!(
+            matches!(result.counterexample, Ok(Some(ref value)) if value == &Data::integer(0.into()))
+        );
+        assert_eq!(result.choices, vec![0]);
+        assert_eq!(result.iterations, 1);
+        assert!(!result.was_shrunk());
+    }
+
+    #[test]
+    fn test_prop_smoke_expected_failure() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) fail {
+                n > 0
+            }
+        "#});
+
+        let result = prop.smoke::<()>();
+
+        assert!(result.is_success());
+        assert!(result.is_expected_failure());
+
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) fail {
+                n < 42
+            }
+        "#});
+
+        let result = prop.smoke::<()>();
+
+        assert!(!result.is_success());
+        assert!(matches!(result.into_property().counterexample, Ok(None)));
+    }
+
+    #[test]
+    fn test_prop_smoke_untried() {
+        let (prop, _) = property(indoc! { r#"
+            fn nothing() -> Fuzzer<Int> {
+              fn(_prng) { None }
+            }
+
+            test foo(n: Int via nothing()) {
+                n < 42
+            }
+        "#});
+
+        let result = prop.smoke::<()>();
+
+        assert!(!result.is_success());
+        assert!(result.is_error());
+
+        let result = result.into_property();
+
+        assert_eq!(result.iterations, 0);
+        assert!(result.lacks_iterations());
+    }
+
+    #[test]
+    fn test_prop_bundle() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 42
+            }
+        "#});
+
+        let result = prop
+            .clone()
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        let bundle = result.bundle().expect("no bundle for a failing property?");
+
+        let bundle: Bundle =
+            serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+
+        let replayed = prop
+            .clone()
+            .load_bundle::<()>(&bundle)
+            .unwrap()
+            .into_property();
+
+        assert_eq!(replayed.seed, result.seed);
+        assert_eq!(replayed.choices, result.choices);
+        assert!(
+            matches!((&replayed.counterexample, &result.counterexample), (Ok(Some(a)), Ok(Some(b))) if a == b)
+        );
+
+        let other = Bundle {
+            name: "bar".to_string(),
+            ..bundle.clone()
+        };
+        assert!(matches!(
+            prop.clone().load_bundle::<()>(&other),
+            Err(BundleError::TestMismatch { .. })
+        ));
+
+        let other = Bundle {
+            version: Bundle::VERSION + 1,
+            ..bundle.clone()
+        };
+        assert!(matches!(
+            prop.clone().load_bundle::<()>(&other),
+            Err(BundleError::UnsupportedVersion { .. })
+        ));
+
+        // Version 1 bundles carry no size, and replay at size 0.
+        let mut legacy = serde_json::to_value(&bundle).unwrap();
+        legacy["version"] = 1.into();
+        legacy.as_object_mut().unwrap().remove("size");
+        let legacy: Bundle = serde_json::from_value(legacy).unwrap();
+        assert_eq!(legacy.size, 0);
+        assert!(prop.load_bundle::<()>(&legacy).is_ok());
+    }
+
+    #[test]
+    fn test_prop_bundle_expected_failure() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) fail {
+                n < 42
+            }
+        "#});
+
+        let result = prop
+            .clone()
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        let bundle = result.bundle().expect("no bundle for a failing property?");
+
+        let bundle: Bundle =
+            serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+
+        let replayed = prop.load_bundle::<()>(&bundle).unwrap();
+
+        assert!(replayed.is_success());
+
+        let replayed = replayed.into_property();
+
+        assert_eq!(replayed.choices, result.choices);
+        assert!(
+            matches!((&replayed.counterexample, &result.counterexample), (Ok(Some(a)), Ok(Some(b))) if a == b)
+        );
+    }
+
+    #[test]
+    fn test_prop_size() {
+        let (prop, reify) = property(indoc! { r#"
+            fn size() -> Fuzzer<Int> {
+              fn(prng: PRNG) -> Option<(PRNG, Int)> {
+                when prng is {
+                  Seeded { size, .. } -> Some((prng, size))
+                  Replayed { size, .. } -> Some((prng, size))
+                }
+              }
+            }
+
+            test foo(n: Int via size()) {
+              n < 30
+            }
+        "#});
+
+        let result = prop.clone().run::<()>(42, 100).into_property();
+
+        assert_eq!(result.size, 30);
+        assert_eq!(
+            result.counterexample.ok().flatten().map(reify),
+            Some("30".to_string())
+        );
+
+        // Replays happen at the size the choices were drawn at, so shrinking a value drawn late
+        // doesn't turn it into one drawn early.
+        let (prop, reify) = property(indoc! { r#"
+            fn offset_by_size() -> Fuzzer<Int> {
+              fn(prng: PRNG) -> Option<(PRNG, Int)> {
+                let size =
+                  when prng is {
+                    Seeded { size, .. } -> size
+                    Replayed { size, .. } -> size
+                  }
+                let fuzzer = int()
+                when fuzzer(prng) is {
+                  Some((next, n)) -> Some((next, n + size))
+                  None -> None
+                }
+              }
+            }
+
+            test foo(n: Int via offset_by_size()) {
+              n < 30
+            }
+        "#});
+
+        let (_, value) = Prng::from_choices(&[5])
+            .with_size(30)
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+        assert_eq!(reify(value.clone()), "35");
+
+        let mut counterexample = Counterexample::sized(&prop, value, vec![5], 30);
+        counterexample.simplify();
+        assert_eq!(counterexample.choices, vec![0]);
+        assert_eq!(reify(counterexample.value), "30");
+    }
+
+    #[test]
+    fn test_prop_stalled_fuzzer() {
+        let (prop, _) = property(indoc! { r#"
+            fn stalled() -> Fuzzer<Int> {
+              fn(prng: PRNG) -> Option<(PRNG, Int)> {
+                when prng is {
+                  Seeded { seed, choices, size } -> {
+                    let choice = builtin.index_bytearray(seed, 0)
+                    Some((
+                      Seeded { seed, choices: builtin.cons_bytearray(choice, choices), size },
+                      choice
+                    ))
+                  }
+                  Replayed { .. } -> None
+                }
+              }
+            }
+
+            test foo(n: Int via stalled()) {
+              n >= 0
+            }
+        "#});
+
+        let result = prop
+            .run_with::<()>(PropertyTestOptions::new(42).record_trail(true))
+            .into_property();
+
+        assert!(result.stalled_fuzzer);
+        assert!(result.trail.iter().unique().count() > 1);
+
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+              n >= 0
+            }
+        "#});
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert!(!result.stalled_fuzzer);
+    }
+
+    #[test]
+    fn test_prop_failed_at() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 42
+            }
+        "#});
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        let failed_at = result.failed_at.expect("property should have failed");
+        assert_eq!(failed_at, result.iterations);
+
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert_eq!(result.failed_at, None);
+    }
+
+    #[test]
+    fn test_prop_eval_error() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 42 || builtin.divide_integer(1, 0) > 0
+            }
+        "#});
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert!(matches!(result.counterexample, Ok(Some(..))));
+        assert!(result.eval_error.is_some());
+
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 42
+            }
+        "#});
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert!(matches!(result.counterexample, Ok(Some(..))));
+        assert!(result.eval_error.is_none());
+    }
+
+    #[test]
+    fn test_prop_failing_program() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        let program = result.failing_program.expect("no failing program?");
+
+        // The program stands on its own: evaluating it yields the failure again.
+        let eval_result = program.clone().eval(ExBudget::max());
+        assert!(has_failed(&eval_result, false));
+        assert!(matches!(
+            eval_result.result(),
+            Ok(Term::Constant(con)) if matches!(con.as_ref(), Constant::Bool(false))
+        ));
+
+        assert_eq!(
+            program,
+            result.test.apply(&result.counterexample.unwrap().unwrap())
+        );
+
+        let (prop, _) = property("test foo(n: Int via int()) { n >= 0 }");
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert!(result.failing_program.is_none());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "fuzzer of 'foo' doesn't yield values of type ByteArray")]
+    fn test_prop_fuzzer_type_mismatch() {
+        let src = format!("{PRELUDE}\ntest foo(n: Int via int()) {{ n < 42 }}");
+
+        Test::from_sources_with(&[], &src, |_| {});
+
+        Test::from_sources_with(&[], &src, |test| {
+            test.arguments[0].tipo = builtins::byte_array();
+        });
+    }
+
+    #[test]
+    fn test_prop_run_raw() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let (value, eval_result) = prop.run_raw(42).unwrap().unwrap();
+
+        let (_, expected) = Prng::from_seed(42, 0)
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(value, expected);
+
+        let n = match &value {
+            PlutusData::BigInt(pallas::ledger::primitives::alonzo::BigInt::Int(n)) => {
+                i128::from(*n)
+            }
+            _ => unreachable!("int fuzzer yielded a non-integer ?!"),
+        };
+
+        assert!(matches!(
+            eval_result.result(),
+            Ok(Term::Constant(con)) if matches!(con.as_ref(), Constant::Bool(b) if *b == (n < 42))
+        ));
+    }
+
+    #[test]
+    fn test_prop_scenario() {
+        let (prop, _) = property(indoc! { r#"
+            fn balance() -> Fuzzer<Int> {
+              int()
+                |> map(
+                     fn(n) {
+                       if n == 0 {
+                         trace @"user with zero balance"
+                         n
+                       } else {
+                         n
+                       }
+                     },
+                   )
+            }
+
+            test foo(n: Int via balance()) {
+              n > 10
+            }
+        "#});
+
+        let result = prop
+            .clone()
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert_eq!(result.choices, vec![0]);
+        assert_eq!(result.scenario, vec!["user with zero balance".to_string()]);
+
+        let (_, _, traces) = Prng::from_choices(&[1])
+            .sample_with_traces(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+
+        assert!(traces.is_empty());
+    }
+
+    #[test]
+    fn test_prop_many_iterations() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        let result = prop.run::<()>(42, 5_000).into_property();
+
+        assert!(matches!(result.counterexample, Ok(None)));
+        assert_eq!(result.iterations, 5_000);
+    }
+
+    #[test]
+    fn test_prop_trail() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        let result = prop
+            .clone()
+            .run_with::<()>(
+                PropertyTestOptions::new(42)
+                    .max_success(60)
+                    .record_trail(true),
+            )
+            .into_property();
+
+        assert_eq!(result.trail.len(), 60);
+        assert!(result.trail.iter().all(|choices| choices.len() == 1));
+        assert!(result.trail.iter().unique().count() > 1);
+
+        let result = prop.run::<()>(42, 60).into_property();
+
+        assert!(result.trail.is_empty());
+    }
+
+    #[test]
+    fn test_prop_shrink_progress() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 42
+            }
+        "#});
+
+        let mut steps = vec![];
+
+        let result = prop.run_with::<()>(
+            PropertyTestOptions::new(42)
+                .max_success(100)
+                .on_shrink(&mut |step| steps.push(step)),
+        );
+
+        assert!(!result.is_success());
+        assert!(!steps.is_empty());
+        assert!(steps.windows(2).all(|w| w[0].choices >= w[1].choices));
+        assert_eq!(steps.last().map(|step| step.summary.as_str()), Some("42"));
+    }
+
+    #[test]
+    fn test_diff_data() {
+        let left = Data::constr(
+            0,
+            vec![
+                Data::integer(1.into()),
+                Data::list(vec![
+                    Data::integer(1.into()),
+                    Data::integer(2.into()),
+                    Data::integer(3.into()),
+                ]),
+            ],
+        );
+
+        let right = Data::constr(
+            0,
+            vec![
+                Data::integer(1.into()),
+                Data::list(vec![
+                    Data::integer(1.into()),
+                    Data::integer(2.into()),
+                    Data::integer(4.into()),
+                ]),
+            ],
+        );
+
+        assert!(diff_data(&left, &left).is_empty());
+
+        let diff = diff_data(&left, &right);
+        assert_eq!(
+            diff,
+            vec![DataDiff {
+                path: vec![DataPathSegment::Field(1), DataPathSegment::Index(2)],
+                left: Data::integer(3.into()),
+                right: Data::integer(4.into()),
+            }]
+        );
+        assert_eq!(
+            diff[0].to_string(),
+            "at field 1, index 2: expected 4, got 3"
+        );
+
+        let diff = diff_data(&left, &Data::list(vec![]));
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].path.is_empty());
+    }
+
+    #[test]
+    fn test_unit_assertion_diff() {
+        let (test, data_types) = unit_with_types(indoc! { r#"
+            type Foo {
+              a: Int,
+              b: List<Int>,
+            }
+
+            test foo() {
+              Foo { a: 1, b: [1, 2, 3] } == Foo { a: 1, b: [1, 2, 4] }
+            }
+        "#});
+
+        let result = test
+            .run::<PlutusData>()
+            .reify(&utils::indexmap::as_ref_values(&data_types))
+            .into_unit();
+
+        assert!(!result.success);
+        assert_eq!(
+            result
+                .diff
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            vec!["at field 1, index 2: expected 4, got 3".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_unit_assertion_common_value() {
+        let render = |src| {
+            let (test, data_types) = unit_with_types(src);
+
+            let result = test
+                .run::<PlutusData>()
+                .reify(&utils::indexmap::as_ref_values(&data_types))
+                .into_unit();
+
+            assert!(!result.success);
+            result
+                .assertion
+                .expect("no assertion?")
+                .to_string(Stream::Stderr, result.test.can_error)
+        };
+
+        let expected = "× expected these not to be equal, but both evaluated to\n│ 2";
+
+        assert_eq!(render("test foo() fail { 1 + 1 == 2 }"), expected);
+        assert_eq!(render("test foo() { 1 + 1 != 2 }"), expected);
+
+        assert!(render("test foo() { 1 + 1 == 3 }").contains("to equal"));
+    }
+
+    #[test]
+    fn test_unit_reify_unknown_type() {
+        let test = unit(indoc! { r#"
+            type Foo {
+              a: Int,
+            }
+
+            test foo() {
+              Foo { a: 1 } == Foo { a: 2 }
+            }
+        "#});
+
+        // Without 'Foo' amongst the known data types, operands are shown as raw data.
+        let result = test.run::<PlutusData>().reify(&IndexMap::new()).into_unit();
+
+        assert!(!result.success);
+        let rendered = result
+            .assertion
+            .expect("no assertion?")
+            .to_string(Stream::Stderr, false);
+        assert!(rendered.contains("│ Constr(0, 1)"));
+        assert!(rendered.contains("│ Constr(0, 2)"));
+    }
+
+    #[test]
+    fn test_unit_non_boolean() {
+        let mut test = unit(indoc! { r#"
+            test foo() {
+              True
+            }
+        "#});
+
+        test.program = Program {
+            version: test.program.version,
+            term: Term::integer(42.into()),
+        };
+
+        let result = test.run::<PlutusData>().into_unit();
+        assert!(!result.success);
+        assert_eq!(result.non_boolean, Some("(con integer 42)".to_string()));
+    }
+
+    #[test]
+    fn test_unit_runtime_error() {
+        let runtime_error = |src: &str| {
+            let result = unit(src).run::<PlutusData>().into_unit();
+            (result.success, result.runtime_error)
+        };
+
+        let (success, error) = runtime_error(indoc! { r#"
+            test foo() {
+              1 / 0 == 0
+            }
+        "#});
+        assert!(!success);
+        assert!(error.is_some());
+
+        assert_eq!(
+            runtime_error(indoc! { r#"
+                test foo() {
+                  1 == 0
+                }
+            "#}),
+            (false, None)
+        );
+
+        assert_eq!(
+            runtime_error(indoc! { r#"
+                test foo() fail {
+                  1 / 0 == 0
+                }
+            "#}),
+            (true, None)
+        );
+    }
+
+    #[test]
+    fn test_unit_approx() {
+        let result = unit(indoc! { r#"
+            test foo() {
+              Approx { actual: 10 / 3, expected: 4, tolerance: 1 }
+            }
+        "#})
+        .run::<PlutusData>()
+        .into_unit();
+
+        assert!(result.success);
+        assert_eq!(result.non_boolean, None);
+    }
+
+    #[test]
+    fn test_unit_conjunction() {
+        let run = |src: &str| {
+            let result = unit(src).run::<PlutusData>().into_unit();
+            (result.success, result.non_boolean)
+        };
+
+        assert_eq!(
+            run(indoc! { r#"
+                test foo() {
+                  [("positive", 1 > 0), ("even", 2 % 2 == 0)]
+                }
+            "#}),
+            (true, None)
+        );
+
+        assert_eq!(
+            run(indoc! { r#"
+                test foo() {
+                  [("positive", 1 > 0), ("even", 3 % 2 == 0)]
+                }
+            "#}),
+            (false, None)
+        );
+    }
+
+    #[test]
+    fn test_prop_conjunction() {
+        let (prop, reify) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+              [("positive", n >= 0), ("small", n < 200)]
+            }
+        "#});
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert_eq!(
+            result.counterexample.ok().flatten().map(reify),
+            Some("200".to_string())
+        );
+        assert_eq!(result.failed_check, Some("small".to_string()));
+    }
+
+    #[test]
+    fn test_unit_expected_error() {
+        let run = |src: &str| {
+            let result = unit(src).run::<PlutusData>().into_unit();
+            (result.success, result.runtime_error)
+        };
+
+        assert_eq!(
+            run(indoc! { r#"
+                /// @fails_with dividing
+                test foo() fail {
+                  trace @"dividing"
+                  1 / 0 == 0
+                }
+            "#}),
+            (true, None)
+        );
+
+        let (success, error) = run(indoc! { r#"
+            /// @fails_with dividing
+            test foo() fail {
+              trace @"subtracting"
+              1 / 0 == 0
+            }
+        "#});
+        assert!(!success);
+        assert!(error.is_some());
+
+        assert_eq!(
+            run(indoc! { r#"
+                /// @fails_with dividing
+                test foo() fail {
+                  trace @"dividing"
+                  1 / 1 == 1
+                }
+            "#}),
+            (false, None)
+        );
+    }
+
+    #[test]
+    fn test_unit_error_kind() {
+        let run = |src: &str| unit(src).run::<PlutusData>().into_unit().error_kind;
+
+        assert_eq!(
+            run(indoc! { r#"
+                test foo() {
+                  True
+                }
+            "#}),
+            None
+        );
+
+        assert_eq!(
+            run(indoc! { r#"
+                test foo() fail {
+                  fail
+                }
+            "#}),
+            Some(ErrorKind::EvaluationFailure)
+        );
+
+        assert_eq!(
+            run(indoc! { r#"
+                test foo() fail {
+                  1 / 0 == 0
+                }
+            "#}),
+            Some(ErrorKind::Builtin)
+        );
+    }
+
+    #[test]
+    fn test_unit_golden() {
+        let run = |src: &str| {
+            let result = unit(src).run::<PlutusData>().into_unit();
+            (result.success, result.golden_mismatch)
+        };
+
+        assert_eq!(
+            run(indoc! { r#"
+                /// @golden (con   bool True)
+                test foo() {
+                  1 + 1 == 2
+                }
+            "#}),
+            (true, None)
+        );
+
+        let (success, mismatch) = run(indoc! { r#"
+            /// @golden (con bool False)
+            test foo() {
+              1 + 1 == 2
+            }
+        "#});
+        assert!(!success);
+        assert_eq!(
+            mismatch,
+            Some(GoldenMismatch {
+                expected: "(con bool False)".to_string(),
+                actual: "(con bool True)".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_unit_expected_logs() {
+        let run = |src: &str| {
+            let result = unit(src).run::<PlutusData>().into_unit();
+            (result.success, result.logs_mismatch)
+        };
+
+        assert_eq!(
+            run(indoc! { r#"
+                /// @log foo
+                /// @log bar
+                test foo() {
+                  trace @"foo"
+                  trace @"bar"
+                  True
+                }
+            "#}),
+            (true, None)
+        );
+
+        let (success, mismatch) = run(indoc! { r#"
+            /// @log foo
+            /// @log bar
+            test foo() {
+              trace @"foo"
+              trace @"baz"
+              True
+            }
+        "#});
+        assert!(!success);
+
+        let mismatch = mismatch.expect("no logs mismatch");
+        assert_eq!(mismatch.actual, vec!["foo".to_string(), "baz".to_string()]);
+        assert_eq!(
+            mismatch.diff(),
+            vec![
+                LogsDiff::Same("foo"),
+                LogsDiff::Missing("bar"),
+                LogsDiff::Unexpected("baz"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unit_structured_traces() {
+        let result = unit(indoc! { r#"
+            fn helper(n: Int) -> Bool {
+              trace @"  helper"
+              n > 0
+            }
+
+            test foo() {
+              trace @"start"
+              helper(1) && helper(2)
+            }
+        "#})
+        .run::<PlutusData>();
+
+        assert_eq!(
+            result.structured_traces(),
+            vec![
+                Trace {
+                    index: 0,
+                    depth: 0,
+                    message: "start".to_string()
+                },
+                Trace {
+                    index: 1,
+                    depth: 1,
+                    message: "helper".to_string()
+                },
+                Trace {
+                    index: 2,
+                    depth: 1,
+                    message: "helper".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prop_approx() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+              Approx { actual: n / 10 * 10, expected: n, tolerance: 9 }
+            }
+        "#});
+
+        assert!(prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .is_success());
+
+        let (prop, reify) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+              Approx { actual: n / 10 * 10, expected: n, tolerance: 5 }
+            }
+        "#});
+
+        assert_eq!(reify(prop.expect_failure().value), "6");
+    }
+
+    #[test]
+    fn test_unit_assertion_operands() {
+        let (test, data_types) = unit_with_types(indoc! { r#"
+            test foo() {
+              1 + 1 == 3
+            }
+        "#});
+
+        let result = test
+            .run::<PlutusData>()
+            .reify(&utils::indexmap::as_ref_values(&data_types));
+
+        let assertion = result.assertion().expect("no assertion on failing test?");
+
+        assert_eq!(assertion.bin_op, BinOp::Eq);
+
+        let (left, right) = assertion.operands().expect("operands weren't evaluated?");
+
+        let fmt = |expr: &UntypedExpr| Formatter::new().expr(expr, false).to_pretty_string(70);
+
+        assert_eq!(fmt(left), "2");
+        assert_eq!(right.mapped_ref(fmt), vec!["3".to_string()]);
+    }
+
+    #[test]
+    fn test_tags() {
+        let (test, _) = Test::from_source(indoc! { r#"
+            /// Checks something slow, on mainnet.
+            ///
+            /// @slow @mainnet
+            test foo() {
+              True
+            }
+        "#});
+
+        assert_eq!(test.tags(), ["slow", "mainnet"]);
+        assert!(test.has_tag("slow"));
+        assert!(!test.has_tag("checks"));
+
+        let (test, _) = Test::from_source(indoc! { r#"
+            /// Mentions @slow in passing.
+            test foo() {
+              True
+            }
+        "#});
+
+        assert!(test.tags().is_empty());
+    }
+
+    #[test]
+    fn test_accessors() {
+        let (unit, _) = Test::from_source("test foo() { 1 + 1 == 2 }");
+        let prop = Test::PropertyTest(property("test bar(n: Int via int()) { n >= 0 }").0);
+
+        assert_eq!(
+            [&unit, &prop]
+                .iter()
+                .map(|test| (test.module(), test.name(), test.is_property()))
+                .collect::<Vec<_>>(),
+            vec![("", "foo", false), ("", "bar", true)]
+        );
+
+        assert_eq!(unit.input_path(), prop.input_path());
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let (unit, _) = Test::from_source("test foo() { 1 + 1 == 2 }");
+        let (prop, _) = property("test bar(n: Int via int()) { n >= 0 }");
+
+        let planned = dry_run(&[unit, Test::PropertyTest(prop)], 42);
+
+        assert_eq!(
+            planned
+                .iter()
+                .map(|p| (p.name.as_str(), p.is_property, p.iterations))
+                .collect::<Vec<_>>(),
+            vec![("foo", false, 1), ("bar", true, 42)]
+        );
+    }
+
+    #[test]
+    fn test_run_summary() {
+        let mut results = [
+            "test foo() { 1 + 1 == 2 }",
+            "test foo() { 1 + 1 == 3 }",
+            "test foo() fail { 1 + 1 == 3 }",
+        ]
+        .into_iter()
+        .map(|src| Test::from_source(src).0.run(42, 10))
+        .collect::<Vec<_>>();
+
+        let (prop, _) = property("test foo(n: Int via int()) { n >= 0 }");
+        results.push(Test::PropertyTest(prop).run(42, 10));
+
+        let summary = RunSummary::new(&results, Duration::from_millis(1337));
+
+        assert_eq!(
+            (
+                summary.total,
+                summary.passed,
+                summary.failed,
+                summary.errored,
+                summary.expected_failures,
+                summary.iterations,
+                summary.duration_ms,
+            ),
+            (4, 2, 1, 0, 1, 13, 1337)
+        );
+
+        assert!(summary.spent_budget.cpu > 0);
+
+        assert_eq!((summary.property_iterations, summary.shrink_steps), (10, 0));
+
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+        results.push(Test::PropertyTest(prop).run(42, 100));
+
+        let summary = RunSummary::new(&results, Duration::from_millis(1337));
+        assert!(summary.property_iterations > 10);
+        assert!(summary.shrink_steps > 0);
+        assert_eq!(
+            summary.property_duration_ms,
+            results
+                .iter()
+                .filter(|result| matches!(result, TestResult::PropertyTestResult(..)))
+                .map(|result| result.duration())
+                .sum::<Duration>()
+                .as_millis() as u64
+        );
+
+        // Sub-millisecond tests still add up.
+        for result in results.iter_mut() {
+            if let TestResult::UnitTestResult(UnitTestResult { duration, .. }) = result {
+                *duration = Duration::from_micros(600);
+            }
+        }
+
+        let summary = RunSummary::new(&results, Duration::from_millis(1337));
+        assert_eq!(summary.unit_duration_ms, 1);
+
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json()).unwrap();
+        assert_eq!(json["expected_failures"], 1);
+        assert!(json["spent_budget"]["mem"].is_number());
+    }
+
+    #[test]
+    fn test_budget_ceiling() {
+        let results = [
+            "test cheap() { True }",
+            "test costly() { 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 == 8 }",
+        ]
+        .into_iter()
+        .map(|src| Test::from_source(src).0.run(42, 10))
+        .collect::<Vec<_>>();
+
+        let budget = |ix: usize| results[ix].as_unit().spent_budget;
+
+        assert!(check_budget_ceiling(&results, budget(1)).is_ok());
+
+        // Property tests aren't checked, whatever they spent.
+        let (prop, _) = property("test foo(n: Int via int()) { n >= 0 }");
+        assert!(
+            check_budget_ceiling(&[prop.run::<()>(42, 10)], ExBudget { mem: 0, cpu: 0 }).is_ok()
+        );
+
+        let error = check_budget_ceiling(&results, budget(0)).unwrap_err();
+
+        assert_eq!(
+            error.offenders,
+            vec![BudgetOverrun {
+                module: String::new(),
+                name: "costly".to_string(),
+                spent_budget: budget(1),
+            }]
+        );
+
+        assert!(error.to_string().starts_with("1 test(s) exceeded"));
+        assert!(error.to_string().contains("\n  .costly (mem: "));
+    }
+
+    #[test]
+    fn test_unit_cost_models() {
+        let test = unit("test foo() { 1 + 1 == 2 }");
+
+        let budget = |result: &TestResult<(Constant, Rc<Type>), PlutusData>| {
+            assert!(result.is_success());
+            result.as_unit().spent_budget
+        };
+
+        let mut pricier = CostModel::default();
+        pricier.builtin_costs.equals_integer.cpu =
+            uplc::machine::cost_model::TwoArguments::ConstantCost(1_000_000);
+
+        let results = test
+            .clone()
+            .run_with_cost_models(vec![CostModel::default(), pricier]);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(budget(&results[0]), budget(&test.run()));
+        assert!(budget(&results[1]).cpu > budget(&results[0]).cpu);
+        assert_eq!(budget(&results[1]).mem, budget(&results[0]).mem);
+    }
+
+    #[test]
+    fn test_unit_run_raw() {
+        let test = unit("test foo() { 1 + 1 == 2 }");
+
+        let eval_result = test.run_raw();
+
+        assert!(matches!(
+            eval_result.result(),
+            Ok(Term::Constant(con)) if matches!(con.as_ref(), Constant::Bool(true))
+        ));
+
+        assert_eq!(
+            eval_result.cost(),
+            test.run::<PlutusData>().into_unit().spent_budget
+        );
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let results = [
+            "test trivial() { True }",
+            "test asserted() { 1 + 1 == 2 }",
+            "test unasserted() { and { True, True } }",
+        ]
+        .into_iter()
+        .map(|src| Test::from_source(src).0.run(42, 10))
+        .collect::<Vec<_>>();
+
+        let error = check_strict(&results).unwrap_err();
+
+        assert_eq!(
+            error.offenders,
+            vec![IneffectiveTest {
+                module: String::new(),
+                name: "trivial".to_string(),
+            }]
+        );
+
+        assert_eq!(
+            error.to_string(),
+            "1 test(s) have no effective assertion:\n  .trivial"
+        );
+
+        assert!(check_strict(&results[1..]).is_ok());
+    }
+
+    #[test]
+    fn test_unit_snapshot() {
+        let run = |src: &str| unit(src).run::<PlutusData>().into_unit().snapshot();
+
+        let dir = std::env::temp_dir().join(format!("aiken-snapshots-{}", std::process::id()));
+
+        let snapshot = run(indoc! { r#"
+            test foo() {
+              trace @"before"
+              True
+            }
+        "#});
+
+        assert_eq!(snapshot.output.as_deref(), Some("(con bool True)"));
+        assert_eq!(snapshot.traces, vec!["before".to_string()]);
+
+        assert_eq!(
+            snapshot.verify(&dir, false).unwrap(),
+            SnapshotOutcome::Created
+        );
+        assert_eq!(
+            snapshot.verify(&dir, false).unwrap(),
+            SnapshotOutcome::Matched
+        );
+
+        let drifted = run(indoc! { r#"
+            test foo() {
+              trace @"after"
+              True
+            }
+        "#});
+
+        assert_eq!(
+            drifted.verify(&dir, false).unwrap(),
+            SnapshotOutcome::Drifted {
+                expected: snapshot.clone()
+            }
+        );
+        assert_eq!(
+            drifted.verify(&dir, true).unwrap(),
+            SnapshotOutcome::Updated { previous: snapshot }
+        );
+        assert_eq!(
+            drifted.verify(&dir, false).unwrap(),
+            SnapshotOutcome::Matched
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_prng_from_label() {
+        assert_eq!(Prng::label_seed("foo", 42), Prng::label_seed("foo", 42));
+        assert_ne!(Prng::label_seed("foo", 42), Prng::label_seed("bar", 42));
+        assert_ne!(Prng::label_seed("foo", 42), Prng::label_seed("foo", 43));
+        assert_eq!(
+            Prng::from_label("foo", 42).uplc(),
+            Prng::from_seed(Prng::label_seed("foo", 42), 0).uplc()
+        );
+    }
+
+    #[test]
+    fn test_run_seed() {
+        assert_eq!(RunSeed::Fixed(42).resolve(), 42);
+        assert!(!RunSeed::Fixed(42).is_random());
+        assert!(RunSeed::Random.is_random());
+
+        assert_eq!(
+            RunSeed::test_seed(42, "foo", "bar"),
+            RunSeed::test_seed(42, "foo", "bar")
+        );
+        assert_ne!(
+            RunSeed::test_seed(42, "foo", "bar"),
+            RunSeed::test_seed(42, "foo", "baz")
+        );
+        assert_ne!(
+            RunSeed::test_seed(42, "foo", "bar"),
+            RunSeed::test_seed(43, "foo", "bar")
+        );
+    }
+
+    #[test]
+    fn test_prng_from_seed_and_choices() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+              n >= 0
+            }
+        "#});
+
+        let primed = Prng::from_seed_and_choices(42, 0, &[1, 2, 3]);
+        assert_eq!(primed.choices(), vec![1, 2, 3]);
+
+        let (primed, value) = primed.sample(&prop.compiled.fuzzer).unwrap().unwrap();
+        let (fresh, fresh_value) = Prng::from_seed(42, 0)
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(primed, Prng::Seeded { .. }));
+        assert_eq!(value, fresh_value);
+        assert_eq!(primed.choices()[..3], [1, 2, 3]);
+        assert_eq!(primed.choices()[3..], fresh.choices()[..]);
+    }
+
+    #[test]
+    fn test_prng_from_source() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+              n >= 0
+            }
+        "#});
+
+        assert_eq!(
+            Prng::from_source(&mut [1, 2, 3].into_iter(), 2).uplc(),
+            Prng::from_choices(&[1, 2]).uplc()
+        );
+
+        // Exhausted sources yield fewer choices.
+        assert_eq!(
+            Prng::from_source(&mut [1, 2, 3].into_iter(), 5).choices(),
+            vec![1, 2, 3]
+        );
+
+        let choices = HashedChoices::new(42).take(100).collect::<Vec<_>>();
+        assert_eq!(
+            choices,
+            HashedChoices::new(42).take(100).collect::<Vec<_>>()
+        );
+        assert_ne!(
+            choices,
+            HashedChoices::new(43).take(100).collect::<Vec<_>>()
+        );
+
+        let (prng, value) = Prng::from_source(&mut HashedChoices::new(42), 1)
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+        assert_eq!(prng.choices(), vec![choices[0]]);
+        assert_eq!(value, Data::integer(choices[0].into()));
+    }
+
+    #[test]
+    fn test_prng_display() {
+        assert_eq!(
+            Prng::from_choices(&[1, 2, 3]).to_string(),
+            "Replayed(choices=[1, 2, 3])"
+        );
+
+        let prng = Prng::from_seed_and_choices(42, 0, &[4, 2]);
+        assert_eq!(
+            prng.to_string(),
+            format!(
+                "Seeded(seed={}, choices=[4, 2])",
+                hex::encode(prng.seed().unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn test_prng_from_result() {
+        let result = |data: PlutusData| Prng::from_result(Term::data(data));
+
+        let prng = Prng::from_choices(&[1, 2]).uplc();
+
+        assert!(matches!(
+            result(Data::constr(0, vec![Data::list(vec![prng.clone(), Data::integer(14.into())])])),
+            Ok(Some((Prng::Replayed { .. }, value))) if value == Data::integer(14.into())
+        ));
+
+        assert!(matches!(result(Data::constr(1, vec![])), Ok(None)));
+
+        assert!(matches!(
+            result(Data::constr(2, vec![])),
+            Err(uplc::machine::Error::DeserialisationError(expected, _)) if expected == "Option<(PRNG, a)>"
+        ));
+
+        assert!(matches!(
+            result(Data::constr(0, vec![Data::list(vec![Data::integer(1.into()), prng])])),
+            Err(uplc::machine::Error::DeserialisationError(expected, _)) if expected == "PRNG"
+        ));
+
+        // PRNGs without a size are still accepted, as being of size 0.
+        let unsized_seeded = Data::constr(
+            0,
+            vec![Data::bytestring(vec![1, 2, 3]), Data::bytestring(vec![])],
+        );
+        match result(Data::constr(
+            0,
+            vec![Data::list(vec![unsized_seeded, Data::integer(14.into())])],
+        )) {
+            Ok(Some((prng @ Prng::Seeded { .. }, _))) => assert_eq!(prng.size(), 1),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let unsized_replayed =
+            Data::constr(1, vec![Data::integer(0.into()), Data::bytestring(vec![])]);
+        match result(Data::constr(
+            0,
+            vec![Data::list(vec![unsized_replayed, Data::integer(14.into())])],
+        )) {
+            Ok(Some((prng @ Prng::Replayed { .. }, _))) => {
+                assert_eq!(prng.size(), 0);
+                assert_eq!(prng.with_size(3).size(), 3);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_prop_estimate_failure_rate() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 255 }");
+
+        let stats = prop.estimate_failure_rate(10, 42);
+
+        assert_eq!(stats.seeds, 10);
+        assert_eq!(stats.errors, 0);
+        assert!(stats.failures > 0 && stats.failures < 10);
+        assert!(stats.examples.len() <= 3);
+        assert!(stats
+            .examples
+            .iter()
+            .all(|(_, value)| *value == Data::integer(255.into())));
+        assert_eq!(
+            stats.failure_rate(),
+            stats.failures as f64 / stats.seeds as f64
+        );
+
+        let (prop, _) = property("test foo(n: Int via int()) { True }");
+
+        let stats = prop.estimate_failure_rate(3, 42);
+
+        assert_eq!(stats.failures, 0);
+        assert_eq!(stats.iterations, 3 * PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert_eq!(stats.failure_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_differential() {
+        let (reference, _) = property("test foo(n: Int via int()) { n < 42 }");
+        let (optimised, _) = property("test foo(n: Int via int()) { 42 > n }");
+        let (faulty, _) = property("test foo(n: Int via int()) { n < 100 }");
+
+        let differential = DifferentialTest::new(
+            reference.compiled.property.clone(),
+            optimised.compiled.property.clone(),
+            reference.compiled.fuzzer.clone(),
+        );
+
+        let result = differential.run(42, PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert!(matches!(result.divergence, Ok(None)));
+        assert_eq!(result.iterations, PropertyTest::DEFAULT_MAX_SUCCESS);
+
+        let differential = DifferentialTest::new(
+            reference.compiled.property.clone(),
+            faulty.compiled.property.clone(),
+            reference.compiled.fuzzer.clone(),
+        );
+
+        match differential
+            .run(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .divergence
+        {
+            Ok(Some(divergence)) => {
+                assert_eq!(divergence.value, Data::integer(42.into()));
+                assert_eq!(divergence.left.ok(), Some(Term::bool(false)));
+                assert_eq!(divergence.right.ok(), Some(Term::bool(true)));
+            }
+            _ => panic!("expected programs to diverge"),
+        }
+
+        // Shrinking gives up right away, leaving the divergence as first drawn.
+        match differential
+            .clone()
+            .shrink_timeout(Duration::ZERO)
+            .run(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .divergence
+        {
+            Ok(Some(divergence)) => {
+                assert_ne!(divergence.value, Data::integer(42.into()));
+            }
+            _ => panic!("expected programs to diverge"),
+        }
+
+        let result = differential
+            .max_choices(0)
+            .run(42, PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert!(matches!(result.divergence, Ok(None)));
+        assert_eq!(result.oversized_input, Some(1));
+        assert_eq!(result.iterations, 0);
+
+        // Iterations only count inputs actually drawn.
+        let (dry, _) = property(indoc! { r#"
+            fn nothing() -> Fuzzer<Int> {
+              fn(_prng) { None }
+            }
+
+            test foo(n: Int via nothing()) {
+              n < 42
+            }
+        "#});
+
+        let differential = DifferentialTest::new(
+            reference.compiled.property.clone(),
+            faulty.compiled.property.clone(),
+            dry.compiled.fuzzer.clone(),
+        );
+
+        let result = differential.run(42, PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert!(matches!(result.divergence, Ok(None)));
+        assert_eq!(result.iterations, 0);
+
+        // Neither program is fed the input the fuzzer failed to generate.
+        let (exiting, _) = property(indoc! { r#"
+            fn exiting() -> Fuzzer<Int> {
+              fn(prng) { Some((prng, 1 / 0)) }
+            }
+
+            test foo(n: Int via exiting()) {
+              n < 42
+            }
+        "#});
+
+        let differential = DifferentialTest::new(
+            reference.compiled.property.clone(),
+            faulty.compiled.property.clone(),
+            exiting.compiled.fuzzer.clone(),
+        );
+
+        let result = differential.run(42, PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert!(result.divergence.is_err());
+        assert_eq!(result.iterations, 0);
+    }
+
+    #[test]
+    fn test_detach() {
+        let (prop, _) = property(indoc! { r#"
+            fn some() -> Fuzzer<Option<Int>> {
+              int() |> map(fn(n) { Some(n) })
+            }
+
+            test foo(n: Option<Int> via some()) {
+              n != Some(42)
+            }
+        "#});
+
+        let detached = match Test::PropertyTest(prop.clone()).detach().0 {
+            Test::PropertyTest(detached) => detached,
+            Test::UnitTest(..) => unreachable!("detached property into a unit test ?!"),
+        };
+
+        assert_eq!(Rc::strong_count(&detached.fuzzer.type_info), 1);
+        assert_eq!(Rc::strong_count(&detached.compiled), 1);
+        assert_eq!(detached.fuzzer.type_info, prop.fuzzer.type_info);
+        assert_eq!(detached.compiled.fuzzer, prop.compiled.fuzzer);
+        assert_eq!(detached.program.to_pretty(), prop.program.to_pretty());
+
+        let counterexample = |prop: PropertyTest| {
+            prop.run::<()>(42, 100)
+                .into_property()
+                .counterexample
+                .ok()
+                .flatten()
+        };
+
+        assert_eq!(counterexample(detached), counterexample(prop));
+
+        let (test, _) = Test::from_source("test foo() { [1, 2] == [1, 2] }");
+
+        match test.clone().detach().0 {
+            Test::UnitTest(detached) => {
+                let (_, tipo) = detached.assertion.as_ref().unwrap().head.as_ref().unwrap();
+                assert_eq!(Rc::strong_count(tipo), 1);
+                assert!(detached.run::<()>().is_success());
+            }
+            Test::PropertyTest(..) => unreachable!("detached unit test into a property ?!"),
+        }
+    }
+
+    #[test]
+    fn test_flaky_tests() {
+        let run = |src: &str, seed: u32| {
+            let (prop, _) = property(src);
+            vec![Test::PropertyTest(prop).run(seed, PropertyTest::DEFAULT_MAX_SUCCESS)]
+        };
+
+        let stable = "test foo(n: Int via int()) { n < 200 }";
+        assert_eq!(flaky_tests(&run(stable, 42), &run(stable, 42)), vec![]);
+
+        // A same property, failing on rare values, is bound to be found failing under some seeds
+        // only; which is exactly what flakiness between two runs looks like.
+        let rare = "test foo(n: Int via int()) { n != 255 }";
+        let (first, second) = (0..)
+            .map(|seed| (run(rare, 42), run(rare, seed)))
+            .find(|(first, second)| first[0].is_success() != second[0].is_success())
+            .unwrap();
+
+        let flaky = flaky_tests(&first, &second);
+
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky[0].name, "foo");
+        assert_eq!(
+            flaky[0].success,
+            (first[0].is_success(), second[0].is_success())
+        );
+    }
+
+    #[test]
+    fn test_prop_shrink_branch_selector() {
+        let (prop, _) = property(indoc! { r#"
+            fn frequency() -> Fuzzer<Int> {
+              int()
+                |> and_then(
+                     fn(selector) {
+                       if selector < 64 {
+                         int()
+                       } else if selector < 128 {
+                         int() |> map(fn(n) { n + 1000 })
+                       } else {
+                         int() |> map(fn(n) { n + 2000 })
+                       }
+                     },
+                   )
+            }
+
+            test foo(n: Int via frequency()) {
+              n < 10
+            }
+        "#});
+
+        let counterexample = prop.expect_failure();
+
+        // Found in the last branch first.
+        assert_eq!(counterexample.original, Data::integer(2149.into()));
+
+        // Both the selector and the payload shrink down, toward the first branch.
+        assert_eq!(counterexample.value, Data::integer(10.into()));
+        assert_eq!(counterexample.choices, vec![0, 10]);
+
+        let (_, replayed) = Prng::from_choices(&counterexample.choices)
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+        assert_eq!(replayed, counterexample.value);
+    }
+
+    #[test]
+    fn test_program_size() {
+        let size = |src: &str| unit(src).program_size();
+
+        assert_eq!(size("test foo() { True }"), 1);
+        assert!(size("test foo() { 1 + 1 == 2 }") > 1);
+
+        let small = size("test foo() { [1] == [1] }");
+        let large = size(indoc! { r#"
+            fn sum(xs: List<Int>) -> Int {
+              when xs is {
+                [] -> 0
+                [x, ..rest] -> x + sum(rest)
+              }
+            }
+
+            test foo() { sum([1, 2, 3]) == 6 }
+        "#});
+        assert!(large > small);
+
+        let (prop, _) = property("test foo(n: Int via int()) { n >= 0 }");
+        assert!(prop.program_size() > 1);
+    }
+
+    #[test]
+    fn test_prop_shrink() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        assert_eq!(
+            prop.shrink(vec![200]),
+            Some((vec![42], Data::integer(42.into())))
+        );
+
+        // Not a counterexample.
+        assert_eq!(prop.shrink(vec![14]), None);
+
+        // Can't be replayed: the fuzzer runs out of choices.
+        assert_eq!(prop.shrink(vec![]), None);
+    }
+
+    #[test]
+    fn test_prop_finding() {
+        let finding = |src: &str| property(src).0.run::<()>(42, 100).into_property().finding();
+
+        assert_eq!(
+            finding("test foo(n: Int via int()) { n < 42 }"),
+            Some(Finding::Counterexample)
+        );
+
+        assert_eq!(
+            finding("test foo(n: Int via int()) fail { n < 42 }"),
+            Some(Finding::ExpectedFailure)
+        );
+
+        assert_eq!(finding("test foo(n: Int via int()) { n >= 0 }"), None);
+    }
+
+    #[test]
+    fn test_prop_corpus() {
+        let (prop, reify) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+              n != 0
+            }
+        "#});
+
+        let options = PropertyTestOptions::new(42).corpus(vec![vec![7], vec![], vec![0]]);
+
+        let result = prop.clone().run_with::<()>(options).into_property();
+
+        assert_eq!(
+            result.counterexample.ok().flatten().map(reify),
+            Some("0".to_string())
+        );
+        assert_eq!(result.failed_at, Some(2));
+        assert_eq!(result.iterations, 2);
+
+        let options = PropertyTestOptions::new(42)
+            .max_success(10)
+            .corpus(vec![vec![7], vec![255]]);
+
+        let result = prop.run_with::<()>(options).into_property();
+
+        assert_eq!(result.iterations, 12);
+    }
+
+    #[test]
+    fn test_prop_max_choices() {
+        let (prop, _) = property(indoc! { r#"
+            fn pair() -> Fuzzer<Int> {
+              map2(int(), int(), fn(a, b) { a + b })
+            }
+
+            test foo(n: Int via pair()) {
+              n >= 0
+            }
+        "#});
+
+        let result = prop
+            .clone()
+            .run_with::<()>(PropertyTestOptions::new(42).max_choices(1));
+
+        assert!(result.is_error());
+
+        let result = result.into_property();
+
+        assert_eq!(result.oversized_input, Some(2));
+        assert_eq!(result.counterexample.ok(), Some(None));
+
+        let options = PropertyTestOptions::new(42)
+            .max_choices(2)
+            .corpus(vec![vec![1, 2, 3]]);
+
+        let result = prop.clone().run_with::<()>(options).into_property();
+
+        assert_eq!(result.oversized_input, Some(3));
+
+        assert!(prop
+            .run_with::<()>(PropertyTestOptions::new(42).max_choices(2))
+            .is_success());
+    }
+
+    #[test]
+    fn test_prop_fuzzed_type() {
+        let (prop, _) = property(indoc! { r#"
+            fn some() -> Fuzzer<Option<Int>> {
+              int() |> map(fn(n) { Some(n) })
+            }
+
+            test foo(n: Option<Int> via some()) {
+              n != Some(1)
+            }
+        "#});
+
+        let result = prop.run::<()>(42, 10).into_property();
+
+        assert_eq!(result.fuzzed_type(), "Option<Int>");
+    }
 
-    fn try_from(body: TypedExpr) -> Result<Self, Self::Error> {
-        match body {
-            TypedExpr::BinOp {
-                name,
-                tipo,
-                left,
-                right,
-                ..
-            } if tipo == bool() => {
-                // 'and' and 'or' are left-associative operators.
-                match (*right).clone().try_into() {
-                    Ok(Assertion {
-                        bin_op,
-                        head: Ok(head),
-                        tail: Ok(tail),
-                        ..
-                    }) if bin_op == name => {
-                        let mut both = vec1![head];
-                        both.extend(tail);
-                        Ok(Assertion {
-                            bin_op: name,
-                            head: Ok(*left),
-                            tail: Ok(both),
-                        })
-                    }
-                    _ => Ok(Assertion {
-                        bin_op: name,
-                        head: Ok(*left),
-                        tail: Ok(vec1![*right]),
-                    }),
+    #[test]
+    fn test_prop_reified_counterexample() {
+        let src = format!(
+            "{PRELUDE}\n{}",
+            indoc! { r#"
+                test foo(n: Int via int()) {
+                  n < 42
                 }
+            "#}
+        );
+
+        let (prop, data_types) = match Test::from_source(&src) {
+            (Test::PropertyTest(prop), data_types) => (prop, data_types),
+            (Test::UnitTest(..), _) => {
+                panic!("Expected to yield a PropertyTest but found a UnitTest")
             }
+        };
 
-            // NOTE drill through trace-if-false operators for better errors.
-            TypedExpr::If {
-                branches,
-                final_else,
-                ..
-            } => {
-                if let [IfBranch {
-                    condition, body, ..
-                }] = &branches[..]
-                {
-                    let then_is_true = match body {
-                        TypedExpr::Var {
-                            name, constructor, ..
-                        } => name == "True" && constructor.tipo == bool(),
-                        _ => false,
-                    };
+        let data_types = utils::indexmap::as_ref_values(&data_types);
 
-                    let else_is_wrapped_false = match *final_else {
-                        TypedExpr::Trace { then, .. } => match *then {
-                            TypedExpr::Var {
-                                name, constructor, ..
-                            } => name == "False" && constructor.tipo == bool(),
-                            _ => false,
-                        },
-                        _ => false,
-                    };
+        let reified =
+            prop.reified_counterexample(12, PropertyTest::DEFAULT_MAX_SUCCESS, &data_types);
 
-                    if then_is_true && else_is_wrapped_false {
-                        return condition.to_owned().try_into();
+        assert_eq!(
+            reified.map(|expr| Formatter::new().expr(&expr, false).to_pretty_string(70)),
+            Some("42".to_string())
+        );
+
+        let (prop, _) = property("test foo(n: Int via int()) { n >= 0 }");
+        assert!(prop.reified_counterexample(12, 10, &data_types).is_none());
+    }
+
+    #[test]
+    fn test_prop_signed_shrinking() {
+        let zigzag = indoc! { r#"
+            fn signed() -> Fuzzer<Int> {
+              int() |> map(fn(n) { if n % 2 == 0 { n / 2 } else { 0 - (n + 1) / 2 } })
+            }
+        "#};
+
+        let (prop, reify) = property(&format!(
+            "{zigzag}\ntest foo(n: Int via signed()) {{ n > -5 }}"
+        ));
+        assert_eq!(reify(prop.expect_failure().value), "-5");
+
+        let (prop, reify) = property(&format!(
+            "{zigzag}\ntest foo(n: Int via signed()) {{ n < 5 }}"
+        ));
+        assert_eq!(reify(prop.expect_failure().value), "5");
+    }
+
+    #[test]
+    fn test_prop_reify_raw() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        let choices = result.choices.clone();
+        let result = result.reify_raw();
+        assert_eq!(
+            result
+                .counterexample
+                .ok()
+                .flatten()
+                .map(|expr| Formatter::new().expr(&expr, false).to_pretty_string(70)),
+            Some("42".to_string())
+        );
+        assert_eq!(result.choices, choices);
+    }
+
+    #[test]
+    fn test_counterexample_new() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let (value, choices) = (0..)
+            .find_map(
+                |seed| match Prng::from_seed(seed, 0).sample(&prop.compiled.fuzzer) {
+                    Ok(Some((prng, value))) if prop.verdict(&value) => {
+                        Some((value, prng.choices()))
                     }
-                }
+                    _ => None,
+                },
+            )
+            .unwrap();
 
-                Err(())
+        let counterexample = Counterexample::new(&prop, value.clone(), choices.clone());
+
+        let mut simplified = counterexample.clone();
+        simplified.simplify();
+
+        assert_eq!(counterexample.value, value);
+        assert_eq!(counterexample.choices, choices);
+        assert_eq!(simplified.original, value);
+        assert_eq!(simplified.value, prop.expect_failure().value);
+    }
+
+    #[test]
+    fn test_prop_shrink_reproducible() {
+        let (prop, _) = property(indoc! { r#"
+            fn triple() -> Fuzzer<(Int, Int, Int)> {
+              map2(int(), map2(int(), int(), fn(b, c) { (b, c) }), fn(a, bc) { (a, bc.1st, bc.2nd) })
             }
 
-            TypedExpr::Trace { then, .. } => (*then).try_into(),
-            TypedExpr::Emit { then, .. } => (*then).try_into(),
+            test foo(xs: (Int, Int, Int) via triple()) {
+              xs.1st + xs.2nd + xs.3rd < 300
+            }
+        "#});
 
-            TypedExpr::Sequence { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
-                if let Ok(Assertion {
-                    bin_op,
-                    head: Ok(head),
-                    tail: Ok(tail),
-                }) = expressions.last().unwrap().to_owned().try_into()
-                {
-                    let replace = |expr| {
-                        let mut expressions = expressions.clone();
-                        expressions.pop();
-                        expressions.push(expr);
-                        TypedExpr::Sequence {
-                            expressions,
-                            location: Span::empty(),
+        let (value, choices) = (0..)
+            .find_map(
+                |seed| match Prng::from_seed(seed, 0).sample(&prop.compiled.fuzzer) {
+                    Ok(Some((prng, value))) if prop.verdict(&value) => {
+                        Some((value, prng.choices()))
+                    }
+                    _ => None,
+                },
+            )
+            .unwrap();
+
+        let shrink = || {
+            let mut counterexample = Counterexample::new(&prop, value.clone(), choices.clone());
+            counterexample.simplify();
+            (
+                counterexample.choices,
+                counterexample.value,
+                counterexample.stats,
+            )
+        };
+
+        let first = shrink();
+
+        assert!(first.2.total() > 0);
+        assert_eq!(first, shrink());
+    }
+
+    #[test]
+    fn test_prop_shrink_canonical() {
+        let fuzzers = indoc! { r#"
+            fn list_of(fuzz: Fuzzer<a>) -> Fuzzer<List<a>> {
+              int()
+                |> and_then(
+                     fn(n) {
+                       if n % 2 == 0 {
+                         constant([])
+                       } else {
+                         map2(fuzz, list_of(fuzz), fn(x, xs) { [x, ..xs] })
+                       }
+                     },
+                   )
+            }
+
+            fn sized(n: Int) -> Fuzzer<List<Int>> {
+              if n <= 0 {
+                constant([])
+              } else {
+                map2(int(), sized(n - 1), fn(x, xs) { [x, ..xs] })
+              }
+            }
+
+            fn ints() -> Fuzzer<List<Int>> {
+              list_of(int())
+            }
+
+            fn prefixed() -> Fuzzer<List<Int>> {
+              int() |> and_then(fn(n) { sized(n / 32) })
+            }
+
+            fn sum(xs: List<Int>) -> Int {
+              when xs is {
+                [] -> 0
+                [x, ..rest] -> x + sum(rest)
+              }
+            }
+
+            fn length(xs: List<Int>) -> Int {
+              when xs is {
+                [] -> 0
+                [_, ..rest] -> 1 + length(rest)
+              }
+            }
+
+            fn any_above(xs: List<Int>, n: Int) -> Bool {
+              when xs is {
+                [] -> False
+                [x, ..rest] -> x > n || any_above(rest, n)
+              }
+            }
+        "# };
+
+        // Each case is shrunk from several counterexamples, all of which must end up at the same
+        // (known) minimum; which must be a fixed point.
+        for (fuzzer, body, choices, value) in [
+            ("ints", "sum(xs) < 100", vec![1, 100, 0], "[100]"),
+            (
+                "ints",
+                "length(xs) < 3",
+                vec![1, 0, 1, 0, 1, 0, 0],
+                "[0, 0, 0]",
+            ),
+            ("ints", "!any_above(xs, 200)", vec![1, 201, 0], "[201]"),
+            ("prefixed", "!any_above(xs, 200)", vec![32, 201], "[201]"),
+            ("prefixed", "length(xs) < 3", vec![96, 0, 0, 0], "[0, 0, 0]"),
+            ("prefixed", "sum(xs) < 300", vec![64, 45, 255], "[45, 255]"),
+        ] {
+            let (prop, reify) = property(&format!(
+                "{fuzzers}\ntest foo(xs: List<Int> via {fuzzer}()) {{ {body} }}"
+            ));
+
+            let counterexamples = (0..)
+                .filter_map(
+                    |seed| match Prng::from_seed(seed, 0).sample(&prop.compiled.fuzzer) {
+                        Ok(Some((prng, value))) if prop.verdict(&value) => {
+                            Some((value, prng.choices()))
                         }
-                    };
+                        _ => None,
+                    },
+                )
+                .take(5);
+
+            for (original, original_choices) in counterexamples {
+                let mut counterexample =
+                    Counterexample::new(&prop, original.clone(), original_choices.clone());
+                counterexample.simplify();
+
+                assert_eq!(
+                    (
+                        &counterexample.choices,
+                        reify(counterexample.value.clone()).as_str()
+                    ),
+                    (&choices, value),
+                    "{fuzzer} / {body}: shrinking {} (choices: {original_choices:?})",
+                    reify(original),
+                );
+
+                let mut again = counterexample.clone();
+                again.simplify();
+                assert_eq!(again.choices, counterexample.choices);
+            }
+        }
+    }
+
+    #[test]
+    fn test_prop_shrink_target() {
+        let (prop, reify) = property(indoc! { r#"
+            /// @shrink_toward 255
+            test foo(n: Int via int()) {
+              n < 100
+            }
+        "#});
+
+        assert_eq!(prop.shrink_target, vec![255]);
+
+        let counterexample = prop.expect_failure();
+        assert_eq!(counterexample.choices, vec![255]);
+        assert_eq!(reify(counterexample.value), "255");
+
+        let (prop, reify) = property(indoc! { r#"
+            fn pair() -> Fuzzer<(Int, Int)> {
+              map2(int(), int(), fn(a, b) { (a, b) })
+            }
+
+            /// @shrink_toward 200
+            test foo(t: (Int, Int) via pair()) {
+              t.1st < 150 || t.2nd < 10
+            }
+        "#});
+
+        let counterexample = prop.expect_failure();
+        assert_eq!(counterexample.choices, vec![200, 10]);
+        assert_eq!(reify(counterexample.value), "(200, 10)");
+    }
+
+    #[test]
+    fn test_binary_search_replace_bounds() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 200 || n > 250 }");
+
+        let (_, value) = Prng::from_choices(&[220])
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+
+        let mut counterexample = Counterexample::new(&prop, value, vec![220]);
+
+        // Neither bound is a counterexample, and the lowest one can't be incremented.
+        for (lo, hi) in [(u8::MAX, u8::MAX), (u8::MAX - 1, u8::MAX)] {
+            let found = counterexample.binary_search_replace(lo, hi, |v| vec![(0, v)], &mut |_| {});
+            assert_eq!(found, hi);
+        }
+
+        assert_eq!(counterexample.choices, vec![220]);
+
+        counterexample.binary_search_replace(0, 220, |v| vec![(0, v)], &mut |_| {});
+
+        assert_eq!(counterexample.choices, vec![200]);
+    }
+
+    #[test]
+    fn test_consider_tie_breaking() {
+        let (prop, _) = property(indoc! { r#"
+            fn pair() -> Fuzzer<(Int, Int)> {
+              map2(int(), int(), fn(a, b) { (a, b) })
+            }
+
+            test foo(t: (Int, Int) via pair()) {
+              t.1st + t.2nd < 100
+            }
+        "#});
+
+        let candidates = [vec![50, 50], vec![100, 0], vec![0, 100], vec![70, 30]];
+
+        // However candidates come, the same (strict lexicographic) minimum is adopted.
+        for permutation in candidates.iter().permutations(candidates.len()) {
+            let (_, value) = Prng::from_choices(&[200, 200])
+                .sample(&prop.compiled.fuzzer)
+                .unwrap()
+                .unwrap();
+
+            let mut counterexample = Counterexample::new(&prop, value, vec![200, 200]);
+
+            for choices in permutation {
+                counterexample.consider(choices, &mut |_| {});
+            }
+
+            assert_eq!(counterexample.choices, vec![0, 100]);
+        }
+    }
+
+    #[test]
+    fn test_deletion_decrements_length() {
+        let (prop, reify) = property(indoc! { r#"
+            fn sized(n: Int) -> Fuzzer<List<Int>> {
+              if n <= 0 {
+                constant([])
+              } else {
+                map2(int(), sized(n - 1), fn(x, xs) { [x, ..xs] })
+              }
+            }
+
+            fn prefixed() -> Fuzzer<List<Int>> {
+              int() |> and_then(sized)
+            }
+
+            fn any_above(xs: List<Int>, n: Int) -> Bool {
+              when xs is {
+                [] -> False
+                [x, ..rest] -> x >= n || any_above(rest, n)
+              }
+            }
+
+            test foo(xs: List<Int> via prefixed()) {
+              !any_above(xs, 100)
+            }
+        "#});
+
+        let shrink = |choices: Vec<u8>| {
+            let (_, value) = Prng::from_choices(&choices)
+                .sample(&prop.compiled.fuzzer)
+                .unwrap()
+                .unwrap();
+
+            let mut counterexample = Counterexample::new(&prop, value, choices);
+            counterexample.explored = Some(Vec::new());
+            counterexample.simplify();
+            counterexample
+        };
+
+        let counterexample = shrink(vec![3, 5, 200, 7]);
+
+        assert_eq!(counterexample.choices, vec![1, 100]);
+        assert_eq!(reify(counterexample.value.clone()), "[100]");
+
+        // Deleting an element alone can't be replayed, since the length still counts it; but it
+        // can along with decrementing the length.
+        let explored = counterexample.explored.unwrap();
+        assert!(explored.contains(&(vec![3, 200, 7], false)));
+        assert!(explored.contains(&(vec![2, 200, 7], true)));
+
+        // The same goes wherever the failing element stands, and however long the list is.
+        for choices in [
+            vec![3, 200, 5, 7],
+            vec![3, 5, 7, 200],
+            vec![6, 1, 2, 3, 4, 5, 200],
+            vec![2, 200, 201],
+        ] {
+            assert_eq!(shrink(choices).choices, vec![1, 100]);
+        }
+    }
+
+    #[test]
+    fn test_shrink_regressions() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let n = PropertyTest::DEFAULT_MAX_SUCCESS;
+
+        let expected = BTreeMap::from([(1, vec![42]), (2, vec![43]), (3, vec![41]), (4, vec![])]);
+
+        assert_eq!(
+            prop.check_shrink_regressions(&expected, n),
+            vec![
+                ShrinkRegression {
+                    seed: 3,
+                    expected: vec![41],
+                    actual: Some(vec![42]),
+                },
+                ShrinkRegression {
+                    seed: 4,
+                    expected: vec![],
+                    actual: Some(vec![42]),
+                },
+            ]
+        );
+
+        let (prop, _) = property("test foo(n: Int via int()) { n >= 0 }");
+
+        assert_eq!(
+            prop.check_shrink_regressions(&BTreeMap::from([(1, vec![0])]), n),
+            vec![ShrinkRegression {
+                seed: 1,
+                expected: vec![0],
+                actual: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_shrink_schedule() {
+        let schedule = ShrinkSchedule::default();
+        assert_eq!(schedule.deletion_chunks(10), vec![8, 4, 2, 1]);
+        assert_eq!(schedule.deletion_chunks(100), vec![32, 16, 8, 4, 2, 1]);
+        assert_eq!(schedule.zeroing, vec![8, 4, 2]);
+
+        let schedule = ShrinkSchedule::default()
+            .deletion(vec![2, 0, 1])
+            .zeroing(vec![0]);
+        assert_eq!(schedule.deletion_chunks(100), vec![2, 1]);
+        assert!(schedule.zeroing.is_empty());
+
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let (_, value) = Prng::from_choices(&[3, 5, 200, 7])
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
+
+        let mut counterexample = Counterexample::new(&prop, value, vec![3, 5, 200, 7]);
+        counterexample.explored = Some(Vec::new());
+        counterexample.schedule = ShrinkSchedule::default().deletion(vec![2]);
+        counterexample.simplify();
+
+        // Past the empty sequence, deletions start from the configured chunks, at the end.
+        let explored = counterexample.explored.unwrap();
+        assert_eq!(explored[1].0, vec![3, 5]);
+
+        assert_eq!(
+            prop.clone()
+                .run_with::<()>(
+                    PropertyTestOptions::new(42).shrink_schedule(
+                        ShrinkSchedule::default()
+                            .deletion(vec![1])
+                            .zeroing(Vec::new())
+                    )
+                )
+                .into_property()
+                .choices,
+            vec![42]
+        );
+    }
+
+    #[test]
+    fn test_explored_candidates() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let (_, value) = Prng::from_choices(&[200])
+            .sample(&prop.compiled.fuzzer)
+            .unwrap()
+            .unwrap();
 
-                    Ok(Assertion {
-                        bin_op,
-                        head: Ok(replace(head)),
-                        tail: Ok(tail.mapped(replace)),
-                    })
-                } else {
-                    Err(())
-                }
-            }
-            _ => Err(()),
-        }
+        let mut counterexample = Counterexample::new(&prop, value.clone(), vec![200]);
+        counterexample.simplify();
+        assert!(counterexample.explored.is_none());
+
+        let mut counterexample = Counterexample::new(&prop, value, vec![200]);
+        counterexample.explored = Some(Vec::new());
+        counterexample.simplify();
+
+        let explored = counterexample.explored.unwrap();
+
+        // Deleting the only choice can't be replayed; the minimal counterexample is adopted
+        // along the way.
+        assert_eq!(explored.first(), Some(&(vec![], false)));
+        assert!(explored.contains(&(vec![42], true)));
+        assert!(explored.contains(&(vec![41], false)));
+        assert_eq!(counterexample.choices, vec![42]);
     }
-}
 
-impl Assertion<UntypedExpr> {
-    #[allow(clippy::just_underscores_and_digits)]
-    pub fn to_string(&self, stream: Stream, expect_failure: bool) -> String {
-        let red = |s: &str| {
-            format!("× {s}")
-                .if_supports_color(stream, |s| s.red())
-                .if_supports_color(stream, |s| s.bold())
-                .to_string()
-        };
+    #[test]
+    fn test_prop_error_choices() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
 
-        if self.head.is_err() {
-            return red("program failed");
-        }
+        let result = prop.run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS);
 
-        fn fmt_side(side: &UntypedExpr, stream: Stream) -> String {
-            let __ = "│".if_supports_color(stream, |s| s.red());
+        assert!(result.into_error(true, None).to_string().contains(
+            "\nseed: 42 (its own seed, as given to 'PropertyTest::run')\nchoices: [42]\n"
+        ));
 
-            Formatter::new()
-                .expr(side, false)
-                .to_pretty_string(60)
-                .lines()
-                .map(|line| format!("{__} {line}"))
-                .collect::<Vec<String>>()
-                .join("\n")
-        }
+        // Within a suite, the suite's seed is what reproduces the failure.
+        assert_eq!(
+            result.into_error(false, Some(14)).to_string(),
+            "foo failed\nseed: 14 (re-run it alone with: aiken check --seed 14 --match-tests \".{foo}\" --exact-match)"
+        );
 
-        let left = fmt_side(self.head.as_ref().unwrap(), stream);
+        // Smoke runs draw from no seed.
+        let (prop, _) = property("test foo(n: Int via int()) { n > 0 }");
+        let result = prop.smoke::<()>();
+        assert!(!result.is_success());
+        assert_eq!(result.into_error(false, Some(14)).to_string(), "foo failed");
 
-        let tail = self.tail.as_ref().unwrap();
+        let result = unit("test foo() { 1 + 1 == 3 }").run::<PlutusData>();
 
-        let right = fmt_side(tail.first(), stream);
+        assert!(!result
+            .into_error(true, Some(14))
+            .to_string()
+            .contains("\nseed: "));
+    }
 
-        format!(
-            "{}{}{}",
-            red("expected"),
-            if expect_failure && self.bin_op == BinOp::Or {
-                " neither\n"
-                    .if_supports_color(stream, |s| s.red())
-                    .if_supports_color(stream, |s| s.bold())
-                    .to_string()
-            } else {
-                "\n".to_string()
-            },
-            if expect_failure {
-                match self.bin_op {
-                    BinOp::And => [
-                        left,
-                        red("and"),
-                        [
-                            tail.mapped_ref(|s| fmt_side(s, stream))
-                                .join(format!("\n{}\n", red("and")).as_str()),
-                            if tail.len() > 1 {
-                                red("to not all be true")
-                            } else {
-                                red("to not both be true")
-                            },
-                        ]
-                        .join("\n"),
-                    ],
-                    BinOp::Or => [
-                        left,
-                        red("nor"),
-                        [
-                            tail.mapped_ref(|s| fmt_side(s, stream))
-                                .join(format!("\n{}\n", red("nor")).as_str()),
-                            red("to be true"),
-                        ]
-                        .join("\n"),
-                    ],
-                    BinOp::Eq => [left, red("to not equal"), right],
-                    BinOp::NotEq => [left, red("to not be different"), right],
-                    BinOp::LtInt => [left, red("to not be lower than"), right],
-                    BinOp::LtEqInt => [left, red("to not be lower than or equal to"), right],
-                    BinOp::GtInt => [left, red("to not be greater than"), right],
-                    BinOp::GtEqInt => [left, red("to not be greater than or equal to"), right],
-                    _ => unreachable!("unexpected non-boolean binary operator in assertion?"),
-                }
-                .join("\n")
-            } else {
-                match self.bin_op {
-                    BinOp::And => [
-                        left,
-                        red("and"),
-                        [
-                            tail.mapped_ref(|s| fmt_side(s, stream))
-                                .join(format!("\n{}\n", red("and")).as_str()),
-                            if tail.len() > 1 {
-                                red("to all be true")
-                            } else {
-                                red("to both be true")
-                            },
-                        ]
-                        .join("\n"),
-                    ],
-                    BinOp::Or => [
-                        left,
-                        red("or"),
-                        [
-                            tail.mapped_ref(|s| fmt_side(s, stream))
-                                .join(format!("\n{}\n", red("or")).as_str()),
-                            red("to be true"),
-                        ]
-                        .join("\n"),
-                    ],
-                    BinOp::Eq => [left, red("to equal"), right],
-                    BinOp::NotEq => [left, red("to not equal"), right],
-                    BinOp::LtInt => [left, red("to be lower than"), right],
-                    BinOp::LtEqInt => [left, red("to be lower than or equal to"), right],
-                    BinOp::GtInt => [left, red("to be greater than"), right],
-                    BinOp::GtEqInt => [left, red("to be greater than or equal to"), right],
-                    _ => unreachable!("unexpected non-boolean binary operator in assertion?"),
-                }
-                .join("\n")
-            }
-        )
+    #[test]
+    fn test_prop_invalid_replays() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
+
+        let result = prop.run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS);
+
+        let error = result.into_error(true, None).to_string();
+
+        // Shrinking first tries to delete all choices, which the fuzzer can't replay.
+        let invalid_replays = result.into_property().invalid_replays;
+        assert!(invalid_replays > 0);
+
+        assert!(error.ends_with(&format!(
+            "\nchoices: [42]\ninvalid replays: {invalid_replays}"
+        )));
+
+        let (prop, _) = property("test foo(n: Int via constant(14)) { n < 0 }");
+
+        assert_eq!(
+            prop.run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+                .into_property()
+                .invalid_replays,
+            0
+        );
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::{
-        module::{CheckedModule, CheckedModules},
-        utils,
-    };
-    use aiken_lang::{
-        ast::{Definition, ModuleKind, TraceLevel, Tracing},
-        builtins,
-        format::Formatter,
-        line_numbers::LineNumbers,
-        parser,
-        parser::extra::ModuleExtra,
-        IdGenerator,
-    };
-    use indoc::indoc;
-    use std::collections::HashMap;
+    #[test]
+    fn test_prop_was_shrunk() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
 
-    const TEST_KIND: ModuleKind = ModuleKind::Lib;
+        assert!(prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property()
+            .was_shrunk());
 
-    impl Test {
-        pub fn from_source(src: &str) -> (Self, IndexMap<DataTypeKey, TypedDataType>) {
-            let id_gen = IdGenerator::new();
+        let (prop, _) = property("test foo(n: Int via constant(14)) { n < 0 }");
 
-            let module_name = "";
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
 
-            let mut module_types = HashMap::new();
-            module_types.insert("aiken".to_string(), builtins::prelude(&id_gen));
-            module_types.insert("aiken/builtin".to_string(), builtins::plutus(&id_gen));
+        assert!(matches!(result.counterexample, Ok(Some(..))));
+        assert!(!result.was_shrunk());
+    }
 
-            let mut warnings = vec![];
-            let (ast, _) = parser::module(src, TEST_KIND).expect("Failed to parse module");
-            let ast = ast
-                .infer(
-                    &id_gen,
-                    TEST_KIND,
-                    module_name,
-                    &module_types,
-                    Tracing::All(TraceLevel::Verbose),
-                    &mut warnings,
-                )
-                .expect("Failed to type-check module.");
+    #[test]
+    fn test_prop_shrink_stats() {
+        let (prop, _) = property("test foo(n: Int via int()) { n < 42 }");
 
-            module_types.insert(module_name.to_string(), ast.type_info.clone());
+        let counterexample = prop.expect_failure();
 
-            let test = ast
-                .definitions()
-                .filter_map(|def| match def {
-                    Definition::Test(test) => Some(test.clone()),
-                    _ => None,
-                })
-                .last()
-                .expect("No test found in declared src?");
+        assert!(counterexample.stats.total() > 0);
 
-            let mut functions = builtins::prelude_functions(&id_gen);
-            let mut data_types = builtins::prelude_data_types(&id_gen);
-            ast.register_definitions(&mut functions, &mut data_types);
+        let result = prop
+            .clone()
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
 
-            let mut module_sources = HashMap::new();
-            module_sources.insert(
-                module_name.to_string(),
-                (src.to_string(), LineNumbers::new(src)),
-            );
+        assert_eq!(result.shrink_stats, counterexample.stats);
 
-            let mut modules = CheckedModules::default();
-            modules.insert(
-                module_name.to_string(),
-                CheckedModule {
-                    kind: TEST_KIND,
-                    extra: ModuleExtra::default(),
-                    name: module_name.to_string(),
-                    code: src.to_string(),
-                    ast,
-                    package: String::new(),
-                    input_path: PathBuf::new(),
-                },
-            );
+        let (prop, _) = property("test foo(n: Int via int()) { True }");
 
-            let mut generator = CodeGenerator::new(
-                utils::indexmap::as_ref_values(&functions),
-                utils::indexmap::as_ref_values(&data_types),
-                utils::indexmap::as_str_ref_values(&module_types),
-                utils::indexmap::as_str_ref_values(&module_sources),
-                Tracing::All(TraceLevel::Verbose),
-            );
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
 
-            (
-                Self::from_function_definition(
-                    &mut generator,
-                    test.to_owned(),
-                    module_name.to_string(),
-                    PathBuf::new(),
-                ),
-                data_types,
-            )
-        }
+        assert_eq!(result.shrink_stats, ShrinkStats::default());
     }
 
-    fn property(src: &str) -> (PropertyTest, impl Fn(PlutusData) -> String) {
-        let prelude = indoc! { r#"
-            use aiken/builtin
-
-            const max_int: Int = 255
+    #[test]
+    fn test_prop_check_value() {
+        let src = format!(
+            "{PRELUDE}\n{}",
+            indoc! { r#"
+                type Point {
+                  x: Int,
+                  y: Int,
+                }
 
-            pub fn int() -> Fuzzer<Int> {
-              fn(prng: PRNG) -> Option<(PRNG, Int)> {
-                when prng is {
-                  Seeded { seed, choices } -> {
-                     let choice =
-                       seed
-                         |> builtin.index_bytearray(0)
-
-                     Some((
-                       Seeded {
-                         seed: builtin.blake2b_256(seed),
-                         choices: builtin.cons_bytearray(choice, choices)
-                       },
-                       choice
-                     ))
-                  }
+                fn point() -> Fuzzer<Point> {
+                  map2(int(), int(), fn(x, y) { Point { x, y } })
+                }
 
-                  Replayed { cursor, choices } -> {
-                    if cursor >= 1 {
-                        let cursor = cursor - 1
-                        Some((
-                          Replayed { choices, cursor },
-                          builtin.index_bytearray(choices, cursor)
-                        ))
-                    } else {
-                        None
-                    }
-                  }
+                test foo(p via point()) {
+                  p.x + p.y <= 400
                 }
-              }
-            }
+            "#}
+        );
 
-            fn bool() -> Fuzzer<Bool> {
-              int() |> map(fn(n) { n % 2 == 0 })
+        let (prop, data_types) = match Test::from_source(&src) {
+            (Test::PropertyTest(test), data_types) => (test, data_types),
+            (Test::UnitTest(..), _) => {
+                panic!("Expected to yield a PropertyTest but found a UnitTest")
             }
+        };
 
-            fn bytearray() -> Fuzzer<ByteArray> {
-              int()
-                |> map(
-                     fn(n) {
-                       n
-                         |> builtin.integer_to_bytearray(True, 32, _)
-                         |> builtin.blake2b_256()
-                         |> builtin.slice_bytearray(8, 4, _)
-                     },
-                   )
-            }
+        let data_types = utils::indexmap::as_ref_values(&data_types);
+
+        let int = |n: i64| UntypedExpr::UInt {
+            location: Span::empty(),
+            value: n.abs().to_string(),
+            base: Base::Decimal {
+                numeric_underscore: false,
+            },
+        };
+
+        let point = |x: UntypedExpr, y: UntypedExpr| UntypedExpr::Call {
+            location: Span::empty(),
+            fun: Box::new(UntypedExpr::Var {
+                location: Span::empty(),
+                name: "Point".to_string(),
+            }),
+            arguments: vec![
+                CallArg {
+                    label: Some("y".to_string()),
+                    location: Span::empty(),
+                    value: y,
+                },
+                CallArg {
+                    label: Some("x".to_string()),
+                    location: Span::empty(),
+                    value: x,
+                },
+            ],
+        };
+
+        let negative = UntypedExpr::UnOp {
+            op: UnOp::Negate,
+            location: Span::empty(),
+            value: Box::new(int(500)),
+        };
+
+        let passes =
+            |value: UntypedExpr| !prop.check_value(&value, &data_types).unwrap().failed(false);
+
+        assert!(passes(point(int(1), int(2))));
+        assert!(passes(point(negative, int(255))));
+        assert!(!passes(point(int(200), int(255))));
+
+        assert!(prop.check_value(&int(1), &data_types).is_err());
+
+        let counterexample = prop.expect_failure().value;
+        let reified =
+            UntypedExpr::reify_data(&data_types, counterexample.clone(), &prop.fuzzer.type_info)
+                .unwrap();
+        assert_eq!(
+            reified.to_data(&data_types, &prop.fuzzer.type_info),
+            Ok(counterexample)
+        );
+    }
 
-            pub fn constant(a: a) -> Fuzzer<a> {
-              fn(s0) { Some((s0, a)) }
+    #[test]
+    fn test_prop_check_determinism() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                trace @"checking n"
+                n < 42
             }
+        "#});
 
-            pub fn and_then(fuzz_a: Fuzzer<a>, f: fn(a) -> Fuzzer<b>) -> Fuzzer<b> {
-              fn(s0) {
-                when fuzz_a(s0) is {
-                  Some((s1, a)) -> f(a)(s1)
-                  None -> None
-                }
-              }
-            }
+        let result = prop.run_with::<()>(
+            PropertyTestOptions::new(42)
+                .max_success(PropertyTest::DEFAULT_MAX_SUCCESS)
+                .check_determinism(true),
+        );
+        assert!(result.is_failure());
+        let result = result.into_property();
 
-            pub fn map(fuzz_a: Fuzzer<a>, f: fn(a) -> b) -> Fuzzer<b> {
-              fn(s0) {
-                when fuzz_a(s0) is {
-                  Some((s1, a)) -> Some((s1, f(a)))
-                  None -> None
-                }
-              }
-            }
+        assert!(!result.nondeterministic);
+        assert!(matches!(result.counterexample, Ok(Some(..))));
 
-            pub fn map2(fuzz_a: Fuzzer<a>, fuzz_b: Fuzzer<b>, f: fn(a, b) -> c) -> Fuzzer<c> {
-              fn(s0) {
-                when fuzz_a(s0) is {
-                  Some((s1, a)) ->
-                    when fuzz_b(s1) is {
-                      Some((s2, b)) -> Some((s2, f(a, b)))
-                      None -> None
+        // Values drawn from a seeded PRNG are offset, but not replayed ones; so replays never
+        // fail, and never lead back to the counterexample.
+        let (prop, _) = property(indoc! { r#"
+            fn flaky() -> Fuzzer<Int> {
+              fn(prng: PRNG) -> Option<(PRNG, Int)> {
+                let fuzzer = int()
+                when fuzzer(prng) is {
+                  Some((next, n)) ->
+                    when prng is {
+                      Seeded { .. } -> Some((next, n + 100))
+                      Replayed { .. } -> Some((next, n))
                     }
                   None -> None
                 }
               }
             }
-        "#};
 
-        let src = format!("{prelude}\n{src}");
+            test foo(n: Int via flaky()) {
+                n < 256
+            }
+        "#});
 
-        match Test::from_source(&src) {
-            (Test::PropertyTest(test), data_types) => {
-                let type_info = test.fuzzer.type_info.clone();
+        let result = prop.clone().run_with::<()>(
+            PropertyTestOptions::new(42)
+                .max_success(PropertyTest::DEFAULT_MAX_SUCCESS)
+                .check_determinism(true),
+        );
+        assert!(result.is_error());
+        let result = result.into_property();
 
-                let reify = move |counterexample| {
-                    let data_type_refs = utils::indexmap::as_ref_values(&data_types);
-                    let expr = UntypedExpr::reify_data(&data_type_refs, counterexample, &type_info)
-                        .expect("Failed to reify value.");
-                    Formatter::new().expr(&expr, false).to_pretty_string(70)
-                };
+        assert!(result.nondeterministic);
 
-                (test, reify)
-            }
-            (Test::UnitTest(..), _) => {
-                panic!("Expected to yield a PropertyTest but found a UnitTest")
-            }
-        }
+        assert!(
+            !prop
+                .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+                .into_property()
+                .nondeterministic
+        );
     }
 
-    impl PropertyTest {
-        fn expect_failure(&self) -> Counterexample {
-            let mut labels = BTreeMap::new();
-            let mut remaining = PropertyTest::DEFAULT_MAX_SUCCESS;
-            match self.run_n_times(&mut remaining, Prng::from_seed(42), &mut labels) {
-                Ok(Some(counterexample)) => counterexample,
-                _ => panic!("expected property to fail but it didn't."),
+    #[test]
+    fn test_prop_shrink_timeout() {
+        let (prop, reify) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n < 10
             }
-        }
+        "#});
+
+        let counterexample = |options| {
+            prop.clone()
+                .run_with::<()>(options)
+                .into_property()
+                .counterexample
+                .ok()
+                .flatten()
+                .map(&reify)
+        };
+
+        let options = || PropertyTestOptions::new(42);
+
+        assert_eq!(counterexample(options()), Some("10".to_string()));
+        assert!(matches!(
+            counterexample(options().shrink_timeout(Duration::ZERO)),
+            Some(n) if n != "10"
+        ));
+        assert_eq!(
+            counterexample(options().shrink_timeout(Duration::from_secs(60))),
+            Some("10".to_string())
+        );
     }
 
     #[test]
-    fn test_prop_basic() {
+    fn test_max_deletion_chunk() {
+        assert_eq!(Counterexample::max_deletion_chunk(0), 8);
+        assert_eq!(Counterexample::max_deletion_chunk(10), 8);
+        assert_eq!(Counterexample::max_deletion_chunk(17), 8);
+        assert_eq!(Counterexample::max_deletion_chunk(18), 8);
+        assert_eq!(Counterexample::max_deletion_chunk(40), 16);
+        assert_eq!(Counterexample::max_deletion_chunk(100), 32);
+        assert_eq!(Counterexample::max_deletion_chunk(128), 64);
+    }
+
+    #[test]
+    fn test_prop_expected_failure() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) fail {
+                n < 10
+            }
+        "#});
+
+        let result = prop.run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert!(result.is_success());
+        assert!(result.is_expected_failure());
+        assert!(!result.is_failure());
+        assert!(!result.is_error());
+
         let (prop, _) = property(indoc! { r#"
             test foo(n: Int via int()) {
-                n >= 0
+                n < 10
             }
         "#});
 
-        assert!(prop
-            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
-            .is_success());
+        let result = prop.run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS);
+        assert!(!result.is_success());
+        assert!(!result.is_expected_failure());
+        assert!(result.is_failure());
+        assert!(!result.is_error());
     }
 
     #[test]
-    fn test_prop_labels() {
+    fn test_prop_untraced() {
         let (prop, _) = property(indoc! { r#"
-            fn label(str: String) -> Void {
-              str
-                |> builtin.append_string(@"\0", _)
-                |> builtin.debug(Void)
+            test foo(n: Int via int()) {
+                trace @"checking n"
+                n < 100
             }
+        "#});
 
-            test foo(head_or_tail via bool()) {
-                if head_or_tail {
-                    label(@"head")
-                } else {
-                    label(@"tail")
-                }
-                True
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert!(matches!(result.counterexample, Ok(Some(..))));
+        assert_eq!(result.traces, vec!["checking n".to_string()]);
+        assert!(!result.untraced_divergence);
+
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                trace builtin.decode_utf8(#"ff")
+                n >= 0
             }
         "#});
 
-        match prop.run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS) {
-            TestResult::UnitTestResult(..) => unreachable!("property returned unit-test result ?!"),
-            TestResult::PropertyTestResult(result) => {
-                assert!(
-                    result
-                        .labels
-                        .iter()
-                        .eq(vec![(&"head".to_string(), &53), (&"tail".to_string(), &47)]),
-                    "labels: {:#?}",
-                    result.labels
-                )
-            }
-        }
+        let result = prop
+            .run::<()>(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        assert!(matches!(result.counterexample, Ok(Some(..))));
+        assert!(result.untraced_divergence);
     }
 
     #[test]
@@ -1543,8 +8696,81 @@ mod test {
 
         counterexample.simplify();
 
-        assert_eq!(counterexample.choices, vec![149, 252]);
-        assert_eq!(reify(counterexample.value), "(149, 252)");
+        assert_eq!(counterexample.choices, vec![146, 255]);
+        assert_eq!(reify(counterexample.value), "(146, 255)");
+    }
+
+    #[test]
+    fn test_prop_simplest() {
+        let (prop, reify) = property(indoc! { r#"
+            fn int_or_zero() -> Fuzzer<Int> {
+                fn(prng) {
+                    when int()(prng) is {
+                        None -> Some((prng, 0))
+                        some -> some
+                    }
+                }
+            }
+
+            test foo(n via int_or_zero()) {
+                n > 100
+            }
+        "#});
+
+        let mut counterexample = prop.expect_failure();
+
+        counterexample.simplify();
+
+        assert_eq!(counterexample.choices, Vec::<u8>::new());
+        assert_eq!(reify(counterexample.value), "0");
+    }
+
+    #[test]
+    fn test_prop_shrink_ignores_logs() {
+        let (prop, reify) = property(indoc! { r#"
+            fn pair(fuzz_a: Fuzzer<a>, fuzz_b: Fuzzer<b>) -> Fuzzer<(a, b)> {
+                map2(fuzz_a, fuzz_b, fn(a, b) { (a, b) })
+            }
+
+            test foo(t: (Int, Int) via pair(int(), int())) {
+                trace @"checking sum"
+                trace if t.1st > t.2nd { @"left" } else { @"right" }
+                t.1st + t.2nd <= 400
+            }
+        "#});
+
+        let choices = vec![255, 255];
+
+        let value = match Prng::from_choices(&choices).sample(&prop.compiled.fuzzer) {
+            Ok(Some((_, value))) => value,
+            _ => panic!("fuzzer didn't replay its choices"),
+        };
+
+        // Shrinking evaluates candidates without capturing their logs, unlike 'eval'.
+        assert!(!prop.eval(&value).logs().is_empty());
+        assert!(prop
+            .compiled
+            .property
+            .apply_data(value.clone())
+            .eval_without_logs(ExBudget::max())
+            .logs()
+            .is_empty());
+
+        let mut counterexample = Counterexample::new(&prop, value.clone(), choices.clone());
+        counterexample.simplify();
+
+        // Shrinking the same failure while capturing logs leads to the same minimal choices.
+        let mut logging_counterexample =
+            Counterexample::with_verdict(&prop.compiled.fuzzer, value, choices, 0, |value| {
+                has_failed(&prop.eval(value), prop.can_error)
+            });
+        logging_counterexample.simplify();
+
+        assert_eq!(counterexample.choices, logging_counterexample.choices);
+        assert_eq!(
+            reify(counterexample.value),
+            reify(logging_counterexample.value)
+        );
     }
 
     #[test]
@@ -1647,6 +8873,79 @@ mod test {
         assert_eq!(reify(counterexample.value), "Bike { wheels: 2 }");
     }
 
+    #[test]
+    fn test_prop_nested_generics() {
+        let (prop, reify) = property(indoc! { r#"
+            fn list(elem: Fuzzer<a>) -> Fuzzer<List<a>> {
+              bool()
+                |> and_then(fn(continue) {
+                    if continue {
+                      map2(elem, list(elem), fn(head, tail) { [head, ..tail] })
+                    } else {
+                      constant([])
+                    }
+                })
+            }
+
+            fn option(elem: Fuzzer<a>) -> Fuzzer<Option<a>> {
+              bool()
+                |> and_then(fn(some) {
+                    if some {
+                      elem |> map(fn(n) { Some(n) })
+                    } else {
+                      constant(None)
+                    }
+                })
+            }
+
+            test foo(es: List<Option<Int>> via list(option(int()))) {
+              when es is {
+                [Some(n), ..] -> n < 42
+                _ -> True
+              }
+            }
+        "#});
+
+        let mut counterexample = prop.expect_failure();
+
+        counterexample.simplify();
+
+        assert_eq!(reify(counterexample.value), "[Some(42)]");
+    }
+
+    #[test]
+    fn test_prop_reify_fallback() {
+        let src = format!(
+            "{PRELUDE}\n{}",
+            indoc! { r#"
+                test foo(n: Int via int()) {
+                    n < 42
+                }
+            "#}
+        );
+
+        let (test, data_types) = Test::from_source(&src);
+
+        let result = test
+            .run(42, PropertyTest::DEFAULT_MAX_SUCCESS)
+            .into_property();
+
+        // Data that doesn't match the fuzzer's type at all.
+        let result = PropertyTestResult {
+            counterexample: Ok(Some(Data::list(vec![Data::integer(1.into())]))),
+            ..result
+        };
+
+        let counterexample = result
+            .reify(&utils::indexmap::as_ref_values(&data_types))
+            .counterexample
+            .ok()
+            .flatten()
+            .map(|expr| Formatter::new().expr(&expr, false).to_pretty_string(70));
+
+        assert_eq!(counterexample, Some("[1]".to_string()));
+    }
+
     #[test]
     fn test_prop_list() {
         let (prop, reify) = property(indoc! { r#"
@@ -1752,6 +9051,219 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_rank_counterexamples() {
+        let ranked = rank_counterexamples(
+            vec![
+                Candidate {
+                    value: "large-but-cheap",
+                    size: 42,
+                    spent_budget: ExBudget { mem: 10, cpu: 100 },
+                    bucket: String::new(),
+                },
+                Candidate {
+                    value: "small-but-expensive",
+                    size: 2,
+                    spent_budget: ExBudget {
+                        mem: 1000,
+                        cpu: 10000,
+                    },
+                    bucket: String::new(),
+                },
+            ],
+            RankingWeights {
+                size: 0.0,
+                budget: 1.0,
+                diversity: 0.0,
+            },
+        );
+
+        assert_eq!(
+            ranked.iter().map(|r| r.value).collect::<Vec<_>>(),
+            vec!["small-but-expensive", "large-but-cheap"]
+        );
+    }
+
+    #[test]
+    fn test_suite_timeout() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        let tests = || {
+            vec![
+                Test::PropertyTest(prop.clone()),
+                Test::PropertyTest(PropertyTest {
+                    name: "bar".to_string(),
+                    ..prop.clone()
+                }),
+            ]
+        };
+
+        let results = run_suite(
+            tests(),
+            42,
+            PropertyTest::DEFAULT_MAX_SUCCESS,
+            Some(Duration::ZERO),
+        );
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| match result {
+                    TestResult::Skipped(t) => (t.name.as_str(), t.reason.as_str()),
+                    TestResult::UnitTestResult(..) | TestResult::PropertyTestResult(..) => {
+                        panic!("unexpected test run: {}", result.title())
+                    }
+                })
+                .collect::<Vec<_>>(),
+            vec![("foo", "suite timeout"), ("bar", "suite timeout")]
+        );
+
+        let results = run_suite(
+            tests(),
+            42,
+            PropertyTest::DEFAULT_MAX_SUCCESS,
+            Some(Duration::from_secs(3600)),
+        );
+        assert_eq!(results.len(), 2);
+        assert!(!results.iter().any(TestResult::is_skipped));
+    }
+
+    #[test]
+    fn test_suite_timeout_reduced_iterations() {
+        let timeout = Duration::from_secs(100);
+
+        let reduced = |elapsed| reduced_iterations(100, Duration::from_secs(elapsed), timeout);
+
+        assert_eq!(reduced(0), None);
+        assert_eq!(reduced(50), None);
+        assert_eq!(reduced(60), Some(80));
+        assert_eq!(reduced(90), Some(20));
+        assert_eq!(reduced(100), Some(1));
+
+        let (prop, _) = property("test foo(n: Int via int()) { n >= 0 }");
+
+        let start = Instant::now() - Duration::from_secs(90);
+
+        let result = Test::PropertyTest(prop)
+            .run_in_suite(start, 42, 100, Some(timeout))
+            .into_property();
+
+        assert_eq!(result.reduced_from, Some(100));
+        assert!(result.iterations <= 20);
+    }
+
+    #[test]
+    fn test_skipped_result() {
+        let skipped: TestResult<(), ()> = TestResult::Skipped(SkippedTest {
+            module: "foo".to_string(),
+            name: "bar".to_string(),
+            reason: "suite timeout".to_string(),
+        });
+
+        assert_eq!(skipped.module(), "foo");
+        assert_eq!(skipped.title(), "bar");
+        assert!(skipped.is_skipped());
+        assert!(skipped.is_success());
+        assert!(!skipped.is_failure());
+        assert!(!skipped.is_error());
+
+        let summary = RunSummary::new(&[skipped], Duration::ZERO);
+        assert_eq!((summary.total, summary.skipped, summary.passed), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_sort_results() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        let unit = unit(indoc! { r#"
+            test foo() {
+              True
+            }
+        "#});
+
+        let mut results = vec![
+            PropertyTest {
+                module: "b".to_string(),
+                ..prop.clone()
+            }
+            .run::<(Constant, Rc<Type>)>(42, 1),
+            prop.clone().run::<(Constant, Rc<Type>)>(42, 1),
+            UnitTest {
+                name: "bar".to_string(),
+                ..unit.clone()
+            }
+            .run::<PlutusData>(),
+            unit.run::<PlutusData>(),
+        ];
+
+        sort_results(&mut results);
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.sort_key())
+                .collect::<Vec<_>>(),
+            vec![
+                ("", "bar", false),
+                ("", "foo", false),
+                ("", "foo", true),
+                ("b", "foo", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let shuffled = |seed| {
+            let mut items = (0..20).collect::<Vec<_>>();
+            shuffle(&mut items, seed);
+            items
+        };
+
+        assert_eq!(shuffled(42), shuffled(42));
+        assert_ne!(shuffled(42), shuffled(43));
+        assert_ne!(shuffled(42), (0..20).collect::<Vec<_>>());
+        assert_eq!(
+            shuffled(42).into_iter().sorted().collect::<Vec<_>>(),
+            (0..20).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_stream_suite() {
+        let (prop, _) = property(indoc! { r#"
+            test foo(n: Int via int()) {
+                n >= 0
+            }
+        "#});
+
+        let tests = vec![
+            Test::PropertyTest(prop.clone()),
+            Test::PropertyTest(PropertyTest {
+                name: "bar".to_string(),
+                ..prop
+            }),
+        ];
+
+        let mut names = stream_suite(tests, 42, PropertyTest::DEFAULT_MAX_SUCCESS, None)
+            .map(|result| {
+                assert!(result.is_success() && !result.is_skipped());
+                result.title().to_string()
+            })
+            .collect::<Vec<_>>();
+
+        names.sort();
+
+        assert_eq!(names, vec!["bar", "foo"]);
+    }
+
     #[test]
     fn test_cache() {
         let called = std::cell::RefCell::new(0);
@@ -1,11 +1,10 @@
 use super::build::{filter_traces_parser, trace_level_parser};
 use aiken_lang::ast::{TraceLevel, Tracing};
 use aiken_project::{
-    test_framework::PropertyTest,
+    test_framework::{PropertyTest, RunSeed},
     watch::{self, watch_project, with_project},
 };
-use rand::prelude::*;
-use std::{path::PathBuf, process};
+use std::{path::PathBuf, process, time::Duration};
 
 #[derive(clap::Args)]
 /// Type-check an Aiken project
@@ -29,7 +28,8 @@ pub struct Args {
     #[clap(long)]
     watch: bool,
 
-    /// An initial seed to initialize the pseudo-random generator for property-tests.
+    /// An initial seed to initialize the pseudo-random generator for property-tests. When
+    /// omitted, one is picked at random and reported along with the results.
     #[clap(long)]
     seed: Option<u32>,
 
@@ -37,6 +37,21 @@ pub struct Args {
     #[clap(long, default_value_t = PropertyTest::DEFAULT_MAX_SUCCESS)]
     max_success: usize,
 
+    /// Maximum duration (in seconds) of the whole test suite. Tests that haven't started once
+    /// elapsed are skipped, and properties started past half of it run fewer iterations.
+    #[clap(long)]
+    suite_timeout: Option<u64>,
+
+    /// Show counterexamples of failing properties as raw data, skipping their conversion to Aiken
+    /// values; which can be slow for large types.
+    #[clap(long)]
+    raw_counterexamples: bool,
+
+    /// Run tests in a random order, derived from the seed, to flush out hidden dependencies
+    /// between them. Results are still reported in the usual order.
+    #[clap(long)]
+    shuffle: bool,
+
     /// Only run tests if they match any of these strings.
     /// You can match a module with `-m aiken/list` or `-m list`.
     /// You can match a test with `-m "aiken/list.{map}"` or `-m "aiken/option.{flatten_1}"`
@@ -80,11 +95,14 @@ pub fn exec(
         trace_level,
         seed,
         max_success,
+        suite_timeout,
+        raw_counterexamples,
+        shuffle,
     }: Args,
 ) -> miette::Result<()> {
-    let mut rng = rand::thread_rng();
+    let seed = seed.map_or(RunSeed::Random, RunSeed::Fixed);
 
-    let seed = seed.unwrap_or_else(|| rng.gen());
+    let suite_timeout = suite_timeout.map(Duration::from_secs);
 
     let result = if watch {
         watch_project(directory.as_deref(), watch::default_filter, 500, |p| {
@@ -95,6 +113,9 @@ pub fn exec(
                 exact_match,
                 seed,
                 max_success,
+                suite_timeout,
+                raw_counterexamples,
+                shuffle,
                 match filter_traces {
                     Some(filter_traces) => filter_traces(trace_level),
                     None => Tracing::All(trace_level),
@@ -110,6 +131,9 @@ pub fn exec(
                 exact_match,
                 seed,
                 max_success,
+                suite_timeout,
+                raw_counterexamples,
+                shuffle,
                 match filter_traces {
                     Some(filter_traces) => filter_traces(trace_level),
                     None => Tracing::All(trace_level),
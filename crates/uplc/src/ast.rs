@@ -737,12 +737,31 @@ impl From<Term<FakeNamedDeBruijn>> for Term<NamedDeBruijn> {
 
 impl Program<NamedDeBruijn> {
     pub fn eval(self, initial_budget: ExBudget) -> EvalResult {
+        self.eval_with_cost_model(CostModel::default(), initial_budget)
+    }
+
+    /// Same as 'eval', but under the given cost model instead of the default one.
+    pub fn eval_with_cost_model(
+        self,
+        cost_model: CostModel,
+        initial_budget: ExBudget,
+    ) -> EvalResult {
+        let mut machine = Machine::new(Language::PlutusV2, cost_model, initial_budget, 200);
+
+        let term = machine.run(self.term);
+
+        EvalResult::new(term, machine.ex_budget, initial_budget, machine.logs)
+    }
+
+    /// Same as 'eval', but logs are discarded as they're emitted; the result holds none.
+    pub fn eval_without_logs(self, initial_budget: ExBudget) -> EvalResult {
         let mut machine = Machine::new(
             Language::PlutusV2,
             CostModel::default(),
             initial_budget,
             200,
-        );
+        )
+        .discard_logs();
 
         let term = machine.run(self.term);
 
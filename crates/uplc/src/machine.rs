@@ -10,7 +10,7 @@ pub mod runtime;
 pub mod value;
 
 use cost_model::{ExBudget, StepKind};
-pub use error::Error;
+pub use error::{Error, ErrorKind};
 use pallas::ledger::primitives::babbage::Language;
 
 use self::{
@@ -48,6 +48,7 @@ pub struct Machine {
     slippage: u32,
     unbudgeted_steps: [u32; 10],
     pub logs: Vec<String>,
+    capture_logs: bool,
     version: Language,
 }
 
@@ -64,10 +65,18 @@ impl Machine {
             slippage,
             unbudgeted_steps: [0; 10],
             logs: vec![],
+            capture_logs: true,
             version,
         }
     }
 
+    /// Drop logs as soon as they're emitted instead of recording them, for evaluations whose
+    /// outcome is all that matters.
+    pub fn discard_logs(mut self) -> Machine {
+        self.capture_logs = false;
+        self
+    }
+
     pub fn run(&mut self, term: Term<NamedDeBruijn>) -> Result<Term<NamedDeBruijn>, Error> {
         use MachineState::*;
 
@@ -324,7 +333,13 @@ impl Machine {
 
         self.spend_budget(cost)?;
 
-        runtime.call(&self.version, &mut self.logs)
+        let value = runtime.call(&self.version, &mut self.logs);
+
+        if !self.capture_logs {
+            self.logs.clear();
+        }
+
+        value
     }
 
     fn lookup_var(&mut self, name: &NamedDeBruijn, env: &[Value]) -> Result<Value, Error> {
@@ -408,7 +423,7 @@ impl From<&Constant> for Type {
 mod tests {
     use num_bigint::BigInt;
 
-    use super::{cost_model::ExBudget, runtime::Compressable};
+    use super::{cost_model::ExBudget, runtime::Compressable, ErrorKind};
     use crate::{
         ast::{Constant, NamedDeBruijn, Program, Term},
         builtins::DefaultFunction,
@@ -660,4 +675,45 @@ mod tests {
 
         assert_eq!(final_term, Term::bool(true))
     }
+
+    #[test]
+    fn error_kind() {
+        let program = |term: Term<NamedDeBruijn>| Program::<NamedDeBruijn> {
+            version: (0, 0, 0),
+            term,
+        };
+
+        assert_eq!(
+            program(Term::Error).eval(ExBudget::default()).error_kind(),
+            Some(ErrorKind::EvaluationFailure)
+        );
+
+        assert_eq!(
+            program(Term::Error)
+                .eval(ExBudget { mem: 0, cpu: 0 })
+                .error_kind(),
+            Some(ErrorKind::OutOfBudget)
+        );
+
+        assert_eq!(
+            program(Term::Apply {
+                function: Term::Apply {
+                    function: Term::Builtin(DefaultFunction::DivideInteger).into(),
+                    argument: Term::Constant(Constant::Integer(1.into()).into()).into(),
+                }
+                .into(),
+                argument: Term::Constant(Constant::Integer(0.into()).into()).into(),
+            })
+            .eval(ExBudget::default())
+            .error_kind(),
+            Some(ErrorKind::Builtin)
+        );
+
+        assert_eq!(
+            program(Term::Constant(Constant::Bool(true).into()))
+                .eval(ExBudget::default())
+                .error_kind(),
+            None
+        );
+    }
 }
@@ -75,3 +75,55 @@ pub enum Error {
     #[error(transparent)]
     Secp256k1(#[from] k256::ecdsa::Error),
 }
+
+/// A coarse classification of evaluation errors, for callers to tell failure modes apart
+/// without matching on every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The program explicitly called 'error'.
+    EvaluationFailure,
+    /// The program ran out of budget.
+    OutOfBudget,
+    /// A builtin failed on its arguments (e.g. a division by zero).
+    Builtin,
+    /// The program is ill-formed (e.g. it applies a non-function, or is open).
+    Machine,
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::EvaluationFailure => ErrorKind::EvaluationFailure,
+            Error::OutOfExError(..) => ErrorKind::OutOfBudget,
+            Error::InvalidStepKind(..)
+            | Error::OpenTermEvaluated(..)
+            | Error::NonPolymorphicInstantiation(..)
+            | Error::NonFunctionalApplication(..)
+            | Error::NonConstrScrutinized(..)
+            | Error::MissingCaseBranch(..)
+            | Error::TypeMismatch(..)
+            | Error::ListTypeMismatch(..)
+            | Error::PairTypeMismatch(..)
+            | Error::UnexpectedBuiltinTermArgument(..)
+            | Error::BuiltinTermArgumentExpected(..)
+            | Error::NotAConstant(..)
+            | Error::MachineNeverReachedDone => ErrorKind::Machine,
+            Error::EmptyList(..)
+            | Error::IntegerToByteStringNegativeSize(..)
+            | Error::IntegerToByteStringNegativeInput(..)
+            | Error::IntegerToByteStringSizeTooBig(..)
+            | Error::IntegerToByteStringSizeTooSmall(..)
+            | Error::Utf8(..)
+            | Error::ByteStringOutOfBounds(..)
+            | Error::ByteStringConsBiggerThanOneByte(..)
+            | Error::DivideByZero(..)
+            | Error::UnexpectedEd25519PublicKeyLength(..)
+            | Error::UnexpectedEd25519SignatureLength(..)
+            | Error::DeserialisationError(..)
+            | Error::OverflowError
+            | Error::Blst(..)
+            | Error::HashToCurveDstTooBig
+            | Error::Secp256k1(..) => ErrorKind::Builtin,
+        }
+    }
+}
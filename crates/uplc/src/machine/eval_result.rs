@@ -1,4 +1,4 @@
-use super::{cost_model::ExBudget, Error};
+use super::{cost_model::ExBudget, Error, ErrorKind};
 use crate::ast::{Constant, NamedDeBruijn, Term};
 
 #[derive(Debug)]
@@ -43,6 +43,11 @@ impl EvalResult {
         }
     }
 
+    /// The kind of error the evaluation failed with, if any.
+    pub fn error_kind(&self) -> Option<ErrorKind> {
+        self.result.as_ref().err().map(Error::kind)
+    }
+
     #[allow(clippy::result_unit_err)]
     pub fn unwrap_constant(self) -> Result<Constant, ()> {
         match self.result {